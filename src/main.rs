@@ -1,17 +1,39 @@
 #![allow(clippy::multiple_crate_versions)]
 
-use cargo_metadata::MetadataCommand;
+use cargo_metadata::semver::Version;
+use cargo_metadata::{DependencyKind, Metadata, MetadataCommand, Package};
 use chrono::Utc;
 use inquire::{Confirm, MultiSelect, Select, Text};
+use std::collections::{HashMap, HashSet};
 use std::env::consts::OS;
-use std::fs::{self, read_to_string, remove_file, File};
+use std::fs::{self, read_to_string, File};
 use std::io::Write;
 use std::path::Path;
 use std::path::MAIN_SEPARATOR_STR;
 use std::process::Command;
+use std::sync::Once;
 use std::vec;
+use toml_edit::DocumentMut;
 const DEV_BRANCH: &str = "develop";
 const FEATURE_BRANCH_PREFIX: &str = "feature";
+const RELEASE_BRANCH_PREFIX: &str = "release";
+const HOTFIX_BRANCH_PREFIX: &str = "hotfix";
+
+const CONFIG: &str = "commiter.toml";
+const GITIGNORE: &str = "target/\npatches/\n.env\n.env.*\n*.log\n";
+const CHANNELS: [&str; 3] = ["alpha", "beta", "rc"];
+const EXCERPT_LINES: usize = 20;
+
+const MINOR_TYPES: [&str; 8] = [
+    "Star",
+    "Cosmic Dawn",
+    "Red Giant",
+    "Stellar Nursery",
+    "First Contact",
+    "Space Elevator",
+    "Supernova",
+    "Expansion",
+];
 
 const LANG: &str = "en_US";
 const HELP: [&str; 68] = [
@@ -98,12 +120,34 @@ fn check(sentence: &str) -> bool {
         .stdout;
     o.is_empty()
 }
-fn get_last_tag() -> String {
+fn get_last_tag(info: &ProjectInfo) -> String {
+    describe(info, false)
+}
+
+fn last_final_tag(info: &ProjectInfo) -> String {
+    describe(info, true)
+}
+
+fn previous_tag(info: &ProjectInfo, v: &str) -> String {
+    if is_pre_release(v) {
+        get_last_tag(info)
+    } else {
+        last_final_tag(info)
+    }
+}
+
+fn is_pre_release(v: &str) -> bool {
+    Version::parse(v).is_ok_and(|v| !v.pre.is_empty())
+}
+
+fn describe(info: &ProjectInfo, final_only: bool) -> String {
     let tag: String = String::from_utf8(
         Command::new("git")
             .arg("describe")
             .arg("--tags")
             .arg("--abbrev=0")
+            .arg(format!("--match={}*", info.tag_prefix()).as_str())
+            .args(final_only.then(|| format!("--exclude={}*-*", info.tag_prefix())))
             .current_dir(".")
             .output()
             .unwrap()
@@ -113,757 +157,3175 @@ fn get_last_tag() -> String {
     let data: Vec<&str> = tag.split('\n').collect();
     (*data.first().expect("msg")).to_string()
 }
-fn get_log() -> String {
-    let log = File::create("log").expect("failed to create log");
-    let d = format!("{}..HEAD", get_last_tag());
-    assert!(Command::new("git")
-        .arg("log")
-        .arg("--format=fuller")
-        .arg(d.as_str())
-        .stdout(log)
-        .current_dir(".")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap()
-        .success());
-    read_to_string("log").expect("failed to parse file")
-}
-
-fn program_or_lib() -> String {
-    if read_to_string("Cargo.toml")
-        .expect("no cargo project")
-        .contains("lib")
-    {
-        String::from("library")
+fn log_range(info: &ProjectInfo, v: &str) -> String {
+    let tag = previous_tag(info, v);
+    if tag.is_empty() {
+        String::from("HEAD")
     } else {
-        String::from("software")
+        format!("{tag}..HEAD")
     }
 }
-fn create_changelog() -> bool {
-    if Path::new("./logs").is_dir().eq(&false) {
-        fs::create_dir_all("./logs").expect("msg");
+
+struct Commit {
+    hash: String,
+    kind: String,
+    scope: String,
+    summary: String,
+    body: String,
+    bang: bool,
+}
+
+impl Commit {
+    fn parse(record: &str) -> Option<Self> {
+        let fields: Vec<&str> = record.trim_start().splitn(3, '\x1f').collect();
+        if fields.len().ne(&3) {
+            return None;
+        }
+        let subject = fields[1];
+        let (head, summary) = subject.split_once(':').unwrap_or(("", subject));
+        let bang = head.ends_with('!');
+        let head = head.trim_end_matches('!');
+        let (kind, scope) = match head.split_once('(') {
+            Some((k, s)) => (k, s.trim_end_matches(')')),
+            None => (head, ""),
+        };
+        Some(Self {
+            hash: fields[0].to_string(),
+            kind: kind.trim().to_string(),
+            scope: scope.trim().to_string(),
+            summary: summary.trim().to_string(),
+            body: fields[2].trim().to_string(),
+            bang,
+        })
     }
-    let filename = format!(
-        "./logs{MAIN_SEPARATOR_STR}{}-{}-changes.md",
-        project(),
-        version()
-    );
-    let logs = get_log();
-    let lines = logs.lines();
-    let mut f = File::create(filename.as_str()).expect("failed to create file");
-    writeln!(
-        f,
-        "# 🚀 {} {} released\n\nToday the `{}`, we are very happy to present the **{}** version of our `{}` {} !\n\nThis release marks a significant step forward for our {} {}.\n\n## Demonstration\n\n{}\n\n## What it's?\n\nIt's {}\n\n## What we do ?\n\n- {}\n\n## Our team\n\n- {}\n\n## Links\n\n- [Source code]({})\n- [Home]({})\n- [Issues]({})\n- [Pull Request]({})\n- [Discussions]({})\n- [Wiki]({})\n- [Projects]({})\n- [Releases]({})\n- [Crates.io](https://crates.io/crates/{}/{})\n",
-        project(),
-        version(),
-        Utc::now().date_naive(),
-        version(),
-        project(),
-        program_or_lib(),
-        program_or_lib(),
-        project(),
-        project(),
-        description(),
-        keywords().join("\n- "),
-        authors().join("\n- "),
-        repository(),
-        homepage(),
-        issues(),
-        pulls_request(),
-        discussions(),
-        wiki(),
-        projects(),
-        releases(),
-        project(),
-        version()
-    )
-    .expect("msg");
-    for t in commit_types_with_help() {
-        let ttt: Vec<&str> = t.split(':').collect();
-        let title: String = (*ttt.last().unwrap()).to_string();
-        writeln!(f, "###{title}\n").expect("msg");
-        for line in lines.clone() {
-            let current = (*ttt.first().unwrap()).to_string();
-            if line.contains(current.as_str()) {
-                let lll = line.split('\n');
-                for l in lll {
-                    let c = l.replace(ttt.first().unwrap(), "");
-                    let cc: Vec<&str> = c.split(':').collect();
-                    let ccc: Vec<&str> = cc.last().unwrap().split('\n').collect();
-                    let message = ccc.join("\n");
-                    writeln!(f, "\n  -{message}").expect("msg");
+
+    fn short_hash(&self) -> &str {
+        self.hash.get(..7).unwrap_or(self.hash.as_str())
+    }
+
+    fn footer(&self, key: &str) -> Option<String> {
+        self.body
+            .lines()
+            .map(str::trim)
+            .find_map(|l| l.strip_prefix(key))
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+    }
+
+    fn breaking(&self) -> Option<String> {
+        self.footer("BREAKING CHANGE:").or_else(|| {
+            if self.bang {
+                Some(self.summary.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    fn migration(&self) -> Option<String> {
+        self.footer("MIGRATION:")
+    }
+
+    fn references(&self) -> Vec<(String, String)> {
+        let mut x: Vec<(String, String)> = Vec::new();
+        for line in self.body.lines().map(str::trim) {
+            for keyword in ["Fixes", "Closes", "Resolves"] {
+                if let Some(rest) = line.strip_prefix(keyword) {
+                    for issue in rest.split([',', ' ']).filter_map(|i| i.strip_prefix('#')) {
+                        if !issue.is_empty() && issue.chars().all(|c| c.is_ascii_digit()) {
+                            x.push((keyword.to_lowercase(), issue.to_string()));
+                        }
+                    }
                 }
             }
         }
+        x
     }
-    writeln!(
-        f,
-        "\n## README\n\n{}\n\n## LICENSE\n\n```\n{}\n```",
-        read_to_string(readme())
-            .expect("readme file not founded")
-            .trim(),
-        read_to_string(license())
-            .expect("LICENSE file not founded")
-            .trim()
+}
+
+fn commits(info: &ProjectInfo, v: &str) -> Vec<Commit> {
+    String::from_utf8(
+        Command::new("git")
+            .arg("log")
+            .arg("--format=%H%x1f%s%x1f%b%x1e")
+            .arg(log_range(info, v).as_str())
+            .arg("--")
+            .args(info.member_path())
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
     )
-    .expect("msg");
-    remove_file("log").expect("failed to remove log");
-    Path::new(filename.as_str()).exists()
+    .expect("msg")
+    .split('\x1e')
+    .filter_map(Commit::parse)
+    .collect()
 }
-fn issues() -> String {
-    let mut x = repository();
-    if x.contains("github") {
-        x.push_str("/issues");
-    } else if x.contains("gitlab") {
-        x.push_str("-/issues");
-    }
-    x
+
+fn is_commit(hash: &str) -> bool {
+    Command::new("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("{hash}^{{commit}}").as_str())
+        .current_dir(".")
+        .output()
+        .expect("git")
+        .status
+        .success()
+}
+
+fn linkify(text: &str, forge: &Forge) -> String {
+    text.split(' ')
+        .map(|word| {
+            let trimmed = word.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c.ne(&'#'));
+            if let Some(issue) = trimmed.strip_prefix('#') {
+                if !issue.is_empty() && issue.chars().all(|c| c.is_ascii_digit()) {
+                    if let Some(url) = forge.issue(issue) {
+                        return word.replace(trimmed, format!("[{trimmed}]({url})").as_str());
+                    }
+                }
+            }
+            if (7..=40).contains(&trimmed.len())
+                && trimmed.chars().all(|c| c.is_ascii_hexdigit())
+                && trimmed.chars().any(|c| c.is_ascii_digit())
+                && is_commit(trimmed)
+            {
+                if let Some(url) = forge.commit(trimmed) {
+                    return word.replace(trimmed, format!("[`{trimmed}`]({url})").as_str());
+                }
+            }
+            word.to_string()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
 }
 
-fn wiki() -> String {
-    let mut x = repository();
-    if x.contains("github") {
-        x.push_str("/wiki");
-    } else if x.contains("gitlab") {
-        x.push_str("-/wikis");
+fn change(c: &Commit, forge: &Forge) -> String {
+    let hash = match forge.commit(c.hash.as_str()) {
+        Some(url) => format!("[`{}`]({url})", c.short_hash()),
+        None => format!("`{}`", c.short_hash()),
+    };
+    let mut x = format!("- {} ({hash})", linkify(c.summary.as_str(), forge));
+    let references: Vec<String> = c
+        .references()
+        .iter()
+        .map(|(keyword, issue)| match forge.issue(issue) {
+            Some(url) => format!("{keyword} [#{issue}]({url})"),
+            None => format!("{keyword} #{issue}"),
+        })
+        .collect();
+    if !references.is_empty() {
+        x.push_str(format!(", {}", references.join(", ")).as_str());
     }
+    x.push('\n');
     x
 }
-fn projects() -> String {
-    let mut x = repository();
-    if x.contains("github") {
-        x.push_str("/projects");
+
+fn changes(info: &ProjectInfo, v: &str) -> String {
+    let forge = forge(info);
+    let commits = commits(info, v);
+    let mut x = String::from("## Changes\n\n");
+    let mut known: HashSet<&str> = HashSet::new();
+    for t in commit_types_with_help() {
+        let (kind, title) = t.split_once(':').unwrap_or((t, t));
+        if !known.insert(kind) {
+            continue;
+        }
+        let selected: Vec<&Commit> = commits.iter().filter(|c| c.kind.eq(kind)).collect();
+        if selected.is_empty() {
+            continue;
+        }
+        x.push_str(format!("### {}\n\n", title.trim()).as_str());
+        for c in selected {
+            x.push_str(change(c, &forge).as_str());
+        }
+        x.push('\n');
+    }
+    let others: Vec<&Commit> = commits
+        .iter()
+        .filter(|c| !known.contains(c.kind.as_str()))
+        .collect();
+    if !others.is_empty() {
+        x.push_str("### Other changes\n\n");
+        for c in others {
+            x.push_str(change(c, &forge).as_str());
+        }
+        x.push('\n');
+    }
+    let included = pre_releases(info, v);
+    if !included.is_empty() {
+        let links: Vec<String> = included
+            .iter()
+            .map(|t| format!("[{t}]({})", forge.tag(t)))
+            .collect();
+        x.push_str(format!("**Pre-releases**: {}\n\n", links.join(", ")).as_str());
+    }
+    let previous = previous_tag(info, v);
+    if !previous.is_empty() {
+        let current = info.tag_name(v);
+        if let Some(diff) = forge.compare(previous.as_str(), current.as_str()) {
+            x.push_str(format!("**Full diff**: [{previous}...{current}]({diff})\n").as_str());
+        }
     }
     x
 }
 
-fn pulls_request() -> String {
-    let mut x = repository();
-    if x.contains("github") {
-        x.push_str("/pulls");
-    } else if x.contains("gitlab") {
-        x.push_str("-/merge_requests");
+fn pre_releases(info: &ProjectInfo, v: &str) -> Vec<String> {
+    if is_pre_release(v) {
+        return Vec::new();
     }
-    x
+    String::from_utf8(
+        Command::new("git")
+            .arg("tag")
+            .arg("--list")
+            .arg("--sort=v:refname")
+            .arg(format!("{}-*", info.tag_name(v)).as_str())
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
+    )
+    .expect("msg")
+    .lines()
+    .map(ToString::to_string)
+    .collect()
 }
 
-fn discussions() -> String {
-    let mut x = repository();
-    if x.contains("github") {
-        x.push_str("/discussions");
+fn breaking_changes(info: &ProjectInfo, v: &str) -> String {
+    let breaking: Vec<Commit> = commits(info, v)
+        .into_iter()
+        .filter(|c| c.breaking().is_some())
+        .collect();
+    if breaking.is_empty() {
+        return String::new();
+    }
+    let mut x = String::from("## ⚠️ Breaking changes\n\n");
+    for c in &breaking {
+        x.push_str(
+            format!(
+                "- {} (`{}` {}({}): {})\n",
+                c.breaking().unwrap_or_default(),
+                c.short_hash(),
+                c.kind,
+                c.scope,
+                c.summary
+            )
+            .as_str(),
+        );
+        if let Some(m) = c.migration() {
+            x.push_str(format!("  - Migration: {m}\n").as_str());
+        }
     }
+    x.push('\n');
     x
 }
 
-fn create_patch() {
-    if Path::new("./patches").exists().eq(&false) {
-        assert!(fs::create_dir_all("./patches").is_ok());
-    }
-    assert!(Command::new("git")
-        .arg("format-patch")
-        .arg("-1")
-        .current_dir("./patches")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap()
-        .success());
+fn parse_version(info: &ProjectInfo, tag: &str) -> Option<Version> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix(info.tag_prefix().as_str()).unwrap_or(tag);
+    Version::parse(tag.trim_start_matches('v')).ok()
 }
-fn create_tag() {
-    let m: String = Text::new("Enter the tag message : ").prompt().unwrap();
 
-    let v: String = Text::new("Enter the tag version : ")
-        .with_default(version().as_str())
-        .prompt()
-        .unwrap();
+fn is_major_bump(from: &Version, to: &Version) -> bool {
+    if from.major.eq(&0) && to.major.eq(&0) {
+        return to.minor.gt(&from.minor);
+    }
+    to.major.gt(&from.major)
+}
 
-    if m.is_empty() || v.is_empty() {
-        create_tag();
+fn breaking_without_major(info: &ProjectInfo, v: &str) -> bool {
+    let (Some(from), Some(to)) = (
+        parse_version(info, previous_tag(info, v).as_str()),
+        parse_version(info, v),
+    ) else {
+        return false;
+    };
+    if is_major_bump(&from, &to) {
+        return false;
     }
-    assert!(Command::new("git")
-        .arg("tag")
-        .arg("-a")
-        .arg(v.as_str())
-        .arg("-m")
-        .arg(m.as_str())
-        .current_dir(".")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap()
-        .success());
-    assert!(Command::new("cargo")
-        .arg("publish")
-        .current_dir(".")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap()
-        .success());
+    let breaking = commits(info, v)
+        .iter()
+        .filter(|c| c.breaking().is_some())
+        .count();
+    if breaking.eq(&0) {
+        return false;
+    }
+    println!(
+        "Warning: {breaking} commit(s) since {from} contain breaking changes but {to} is not a major version bump."
+    );
+    true
 }
 
-fn send_tag() {
-    assert!(Command::new("git")
-        .arg("push")
-        .arg("--all")
-        .current_dir(".")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap()
-        .success());
-    assert!(Command::new("git")
-        .arg("push")
-        .arg("--tags")
-        .current_dir(".")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap()
-        .success());
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Bump {
+    Patch,
+    Minor,
+    Major,
 }
-fn commit(m: &str) -> bool {
-    assert!(Command::new("git")
-        .arg("commit")
-        .arg("-m")
-        .arg(m)
-        .current_dir(".")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap()
-        .success());
-    create_patch();
-    if confirm("Create new tag", false) {
-        create_tag();
-        send_tag();
+
+impl Bump {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        }
     }
-    true
 }
 
-fn diff() -> bool {
-    Command::new("git")
-        .arg("diff")
-        .current_dir(".")
-        .spawn()
-        .expect("git")
-        .wait()
-        .unwrap()
-        .success()
+fn bump_from_commits(commits: &[Commit]) -> Bump {
+    commits
+        .iter()
+        .map(|c| {
+            if c.breaking().is_some() {
+                Bump::Major
+            } else if MINOR_TYPES.contains(&c.kind.as_str()) {
+                Bump::Minor
+            } else {
+                Bump::Patch
+            }
+        })
+        .max()
+        .unwrap_or(Bump::Patch)
 }
 
-fn fmt() {
-    assert!(Command::new("cargo")
-        .arg("fmt")
-        .current_dir(".")
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap()
-        .success());
-    clear();
+fn bump_version(current: &Version, bump: Bump) -> Version {
+    let bump = if current.major.eq(&0) && bump.eq(&Bump::Major) {
+        Bump::Minor
+    } else {
+        bump
+    };
+    if !current.pre.is_empty() {
+        return Version::new(current.major, current.minor, current.patch);
+    }
+    match bump {
+        Bump::Major => Version::new(current.major + 1, 0, 0),
+        Bump::Minor => Version::new(current.major, current.minor + 1, 0),
+        Bump::Patch => Version::new(current.major, current.minor, current.patch + 1),
+    }
 }
 
-fn zuu() -> bool {
-    clear();
+fn supersedes(version: &Version, last: &Version) -> bool {
+    version.cmp_precedence(last).is_gt()
+}
 
-    if Path::new("Cargo.toml").exists() {
-        fmt();
-        if Command::new("zuu")
-            .current_dir(".")
-            .spawn()
-            .unwrap()
-            .wait()
-            .unwrap()
-            .success()
-        {
-            clear();
-            return true;
+fn next_version(info: &ProjectInfo) -> (Version, Bump) {
+    let current =
+        parse_version(info, info.version().as_str()).expect("invalid version in Cargo.toml");
+    let bump = bump_from_commits(&commits(info, info.version().as_str()));
+    if let Some(last) = parse_version(info, get_last_tag(info).as_str()) {
+        if current.gt(&last) {
+            return (current, bump);
         }
-        return false;
     }
-    clear();
-    true
+    (bump_version(&current, bump), bump)
 }
 
-fn version() -> String {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package = metadata.packages.first().unwrap();
-    package.version.to_string()
+fn pre_release(current: &Version, target: &Version, channel: &str) -> Version {
+    if current.pre.is_empty() {
+        return Version::parse(format!("{target}-{channel}.1").as_str()).expect("msg");
+    }
+    let core = Version::new(current.major, current.minor, current.patch);
+    let n = match current.pre.as_str().split_once('.') {
+        Some((c, n)) if c.eq(channel) => n.parse::<u64>().unwrap_or(0) + 1,
+        _ => 1,
+    };
+    Version::parse(format!("{core}-{channel}.{n}").as_str()).expect("msg")
 }
 
-fn dependencies() -> Vec<String> {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    let dependency_names: Vec<String> = package
-        .dependencies
-        .iter()
-        .map(|dep| dep.name.clone())
-        .collect();
-    dependency_names
+fn channels(current: &Version) -> Vec<&'static str> {
+    let channel = current.pre.as_str().split('.').next().unwrap_or_default();
+    match CHANNELS.iter().position(|c| c.eq(&channel)) {
+        Some(p) => CHANNELS[p..].to_vec(),
+        None => CHANNELS.to_vec(),
+    }
 }
 
-fn releases() -> String {
-    let mut x = repository();
-    if x.contains("github") {
-        x.push_str("/releases");
-    } else if x.contains("gitlab") {
-        x.push_str("-/tags");
+fn choose_version(info: &ProjectInfo) -> String {
+    let current =
+        parse_version(info, info.version().as_str()).expect("invalid version in Cargo.toml");
+    let (proposed, bump) = next_version(info);
+    let mut choices = if current.pre.is_empty() {
+        vec![
+            format!("{proposed} (proposed, {} bump)", bump.name()),
+            format!("{} (major)", bump_version(&current, Bump::Major)),
+            format!("{} (minor)", bump_version(&current, Bump::Minor)),
+            format!("{} (patch)", bump_version(&current, Bump::Patch)),
+        ]
+    } else {
+        vec![format!("{proposed} (proposed, promote {current} to final)")]
+    };
+    for channel in channels(&current) {
+        choices.push(format!(
+            "{} ({channel})",
+            pre_release(&current, &proposed, channel)
+        ));
     }
-    x
+    choices.push(String::from("build metadata"));
+    choices.push(String::from("custom"));
+    let choice = Select::new("Select the next version : ", choices)
+        .prompt()
+        .unwrap();
+    let v = match choice.as_str() {
+        "build metadata" => format!(
+            "{proposed}+{}",
+            Text::new("Enter the build metadata : ").prompt().unwrap()
+        ),
+        "custom" => Text::new("Enter the version : ")
+            .with_default(proposed.to_string().as_str())
+            .prompt()
+            .unwrap(),
+        c => c.split(' ').next().unwrap_or_default().to_string(),
+    };
+    let Some(version) = parse_version(info, v.as_str()) else {
+        println!("{v} is not a valid semantic version");
+        return choose_version(info);
+    };
+    if let Some(last) = parse_version(info, get_last_tag(info).as_str()) {
+        if !supersedes(&version, &last) {
+            println!("{v} must be greater than the last released version {last}");
+            return choose_version(info);
+        }
+    }
+    v
 }
-fn project() -> String {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    package.name.to_string()
+
+fn manifest(path: &str) -> DocumentMut {
+    read_to_string(path)
+        .expect("no cargo project")
+        .parse::<DocumentMut>()
+        .expect("Cargo.toml is not a valid toml file")
 }
 
-fn keywords() -> Vec<String> {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    package.keywords.clone()
+fn versioned_manifest(info: &ProjectInfo, v: &str) -> (String, String) {
+    let path = info.manifest_path();
+    let mut member = manifest(path.as_str());
+    let inherited = member["package"]["version"]
+        .get("workspace")
+        .and_then(toml_edit::Item::as_bool)
+        .unwrap_or(false);
+    if inherited {
+        let root = info.root_manifest();
+        let mut workspace = manifest(root.as_str());
+        workspace["workspace"]["package"]["version"] = toml_edit::value(v);
+        return (root, workspace.to_string());
+    }
+    member["package"]["version"] = toml_edit::value(v);
+    (path, member.to_string())
 }
 
-fn homepage() -> String {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    package.clone().homepage.expect("no homepage")
+fn inherited_members(info: &ProjectInfo) -> Vec<String> {
+    info.metadata
+        .workspace_packages()
+        .iter()
+        .filter(|p| {
+            manifest(p.manifest_path.as_str())["package"]["version"]
+                .get("workspace")
+                .and_then(toml_edit::Item::as_bool)
+                .unwrap_or(false)
+        })
+        .map(|p| p.name.clone())
+        .collect()
 }
 
-fn readme() -> String {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    package
-        .clone()
-        .readme
-        .expect("no readme define")
-        .to_string()
+fn inherited_bump_confirmed(info: &ProjectInfo, v: &str) -> bool {
+    let inherited = inherited_members(info);
+    if inherited.len().le(&1) || !inherited.iter().any(|m| m.eq(info.name())) {
+        return true;
+    }
+    println!(
+        "{} inherits the workspace version, {v} also applies to : {}",
+        info.name(),
+        inherited.join(", ")
+    );
+    println!("Only {} will be tagged", info.tag_name(v));
+    confirm("Bump all of them ?", false)
 }
 
-fn license() -> String {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    package
-        .clone()
-        .license_file
-        .expect("no licences define")
-        .to_string()
+fn set_version(info: &ProjectInfo, v: &str) -> bool {
+    let (path, content) = versioned_manifest(info, v);
+    fs::write(path, content).is_ok()
 }
 
-fn repository() -> String {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    package.clone().repository.expect("no repository define")
+fn backup(files: &[String]) -> Vec<(String, Option<String>)> {
+    let mut x: Vec<(String, Option<String>)> = Vec::new();
+    for f in files {
+        if !x.iter().any(|(file, _)| file.eq(f)) {
+            x.push((f.clone(), read_to_string(f).ok()));
+        }
+    }
+    x
 }
 
-///
-/// # Panics
-///
-fn description() -> String {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    package
-        .description
-        .as_ref()
-        .expect("missing description")
-        .to_string()
+fn restore(files: &[(String, Option<String>)]) {
+    for (file, content) in files {
+        match content {
+            Some(c) => fs::write(file, c).expect("failed to restore file"),
+            None => {
+                if Path::new(file.as_str()).exists() {
+                    fs::remove_file(file).expect("failed to remove file");
+                }
+            }
+        }
+    }
+    println!("Version bump rolled back");
 }
 
-fn authors() -> Vec<String> {
-    let metadata = MetadataCommand::new().no_deps().exec().unwrap();
-    let package: &cargo_metadata::Package = metadata.packages.first().unwrap();
-    package.authors.clone()
+fn is_tracked(file: &str) -> bool {
+    Command::new("git")
+        .arg("ls-files")
+        .arg("--error-unmatch")
+        .arg(file)
+        .current_dir(".")
+        .output()
+        .expect("git")
+        .status
+        .success()
 }
-fn clear() {
-    if OS.eq("windows") {
-        assert!(Command::new("cls")
-            .current_dir(".")
-            .spawn()
+
+fn release_version(info: &mut ProjectInfo, v: &str, extra: &[String]) -> bool {
+    prepare_version(info, v, backup(extra))
+        .is_some_and(|files| commit_version(info, v, &files, extra))
+}
+
+fn prepare_version(
+    info: &ProjectInfo,
+    v: &str,
+    mut files: Vec<(String, Option<String>)>,
+) -> Option<Vec<(String, Option<String>)>> {
+    files.extend(backup(&[
+        info.manifest_path(),
+        info.root_manifest(),
+        info.lock_file(),
+    ]));
+    if !set_version(info, v) {
+        restore(&files);
+        return None;
+    }
+    if !Command::new("cargo")
+        .arg("update")
+        .arg("--workspace")
+        .current_dir(".")
+        .spawn()
+        .expect("cargo")
+        .wait()
+        .unwrap()
+        .success()
+    {
+        println!("cargo update --workspace failed");
+        restore(&files);
+        return None;
+    }
+    Some(files)
+}
+
+fn commit_version(
+    info: &mut ProjectInfo,
+    v: &str,
+    files: &[(String, Option<String>)],
+    extra: &[String],
+) -> bool {
+    let staged: Vec<&str> = files
+        .iter()
+        .map(|(f, _)| f.as_str())
+        .filter(|f| is_tracked(f) || extra.iter().any(|e| e.eq(f)))
+        .collect();
+    let committed = Command::new("git")
+        .arg("add")
+        .args(&staged)
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+        && Command::new("git")
+            .arg("commit")
+            .args(sign("commits"))
+            .arg("-m")
+            .arg(
+                format!(
+                    "Launch({}): Release {} version {v}",
+                    info.tag_name(v),
+                    info.name()
+                )
+                .as_str(),
+            )
+            .arg("--")
+            .args(&staged)
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success();
+    if !committed {
+        println!("The release commit failed");
+        let unstaged = Command::new("git")
+            .arg("reset")
+            .arg("--quiet")
+            .arg("--")
+            .args(&staged)
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .is_ok_and(|s| s.success());
+        if !unstaged {
+            println!("Cannot unstage {}, check the index", staged.join(", "));
+        }
+        restore(files);
+    } else {
+        info.reload();
+    }
+    committed
+}
+
+fn bump(info: &mut ProjectInfo) -> bool {
+    let v = choose_version(info);
+    if breaking_without_major(info, v.as_str()) && !confirm("Bump the version anyway ?", false) {
+        return bump(info);
+    }
+    if !inherited_bump_confirmed(info, v.as_str()) {
+        println!("Bump cancelled");
+        return true;
+    }
+    if !release_version(info, v.as_str(), &[]) {
+        println!("{} stays at {}", info.name(), info.version());
+    }
+    true
+}
+
+struct Contributor {
+    name: String,
+    email: String,
+    commits: usize,
+    added: usize,
+    removed: usize,
+    files: HashSet<String>,
+}
+
+fn previous_authors(info: &ProjectInfo, v: &str) -> HashSet<String> {
+    let tag = previous_tag(info, v);
+    if tag.is_empty() {
+        return HashSet::new();
+    }
+    String::from_utf8(
+        Command::new("git")
+            .arg("log")
+            .arg("--format=%aE")
+            .arg(tag.as_str())
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
+    )
+    .expect("msg")
+    .lines()
+    .map(str::to_lowercase)
+    .collect()
+}
+
+fn contributors(info: &ProjectInfo, v: &str) -> Vec<Contributor> {
+    let log = String::from_utf8(
+        Command::new("git")
+            .arg("log")
+            .arg("--numstat")
+            .arg("--format=%x1e%aN%x1f%aE")
+            .arg(log_range(info, v).as_str())
+            .arg("--")
+            .args(info.member_path())
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
+    )
+    .expect("msg");
+    let mut team: HashMap<String, Contributor> = HashMap::new();
+    for record in log.split('\x1e').filter(|r| !r.trim().is_empty()) {
+        let mut lines = record.lines();
+        let header: Vec<&str> = lines.next().unwrap_or_default().split('\x1f').collect();
+        let name = (*header.first().unwrap_or(&"")).to_string();
+        let email = (*header.get(1).unwrap_or(&"")).to_lowercase();
+        let c = team.entry(email.clone()).or_insert_with(|| Contributor {
+            name,
+            email,
+            commits: 0,
+            added: 0,
+            removed: 0,
+            files: HashSet::new(),
+        });
+        c.commits += 1;
+        for stat in lines.filter(|l| !l.trim().is_empty()) {
+            let parts: Vec<&str> = stat.splitn(3, '\t').collect();
+            if parts.len().ne(&3) {
+                continue;
+            }
+            c.added += parts[0].parse::<usize>().unwrap_or(0);
+            c.removed += parts[1].parse::<usize>().unwrap_or(0);
+            c.files.insert(parts[2].to_string());
+        }
+    }
+    let mut x: Vec<Contributor> = team.into_values().collect();
+    x.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.name.cmp(&b.name)));
+    x
+}
+
+fn team(info: &ProjectInfo, v: &str) -> String {
+    let contributors = contributors(info, v);
+    if contributors.is_empty() {
+        return format!("- {}", info.authors().join("\n- "));
+    }
+    let previous = previous_authors(info, v);
+    let mut x = String::from(
+        "| Contributor | Commits | Lines added | Lines removed | Files touched |\n| :-- | --: | --: | --: | --: |\n",
+    );
+    for c in &contributors {
+        x.push_str(
+            format!(
+                "| {} | {} | +{} | -{} | {} |\n",
+                c.name,
+                c.commits,
+                c.added,
+                c.removed,
+                c.files.len()
+            )
+            .as_str(),
+        );
+    }
+    let newcomers: Vec<&Contributor> = contributors
+        .iter()
+        .filter(|c| !previous.contains(&c.email))
+        .collect();
+    if !newcomers.is_empty() && !previous.is_empty() {
+        x.push_str("\n### First-time contributors\n\n");
+        for c in newcomers {
+            x.push_str(format!("- {} made their first contribution\n", c.name).as_str());
+        }
+    }
+    x.trim_end().to_string()
+}
+
+fn program_or_lib(info: &ProjectInfo) -> String {
+    if info.is_library() {
+        String::from("library")
+    } else {
+        String::from("software")
+    }
+}
+fn changelog_path(name: &str, v: &str) -> String {
+    format!("./logs{MAIN_SEPARATOR_STR}{name}-{v}-changes.md")
+}
+
+fn changelog(info: &ProjectInfo, v: &str) -> Result<String, String> {
+    let description = info.description()?;
+    Ok(format!(
+        "# 🚀 {} {v} released\n\n{}Today the `{}`, we are very happy to present the **{v}** version of our `{}` {} !\n\nThis release marks a significant step forward for our {} {}.\n\n## Demonstration\n\n{}\n\n## What it's?\n\nIt's {description}\n\n## What we do ?\n\n- {}\n\n## Our team\n\n{}\n\n## Links\n\n{}\n\n{}{}{}",
+        info.name(),
+        breaking_changes(info, v),
+        Utc::now().date_naive(),
+        info.name(),
+        program_or_lib(info),
+        program_or_lib(info),
+        info.name(),
+        info.name(),
+        info.keywords().join("\n- "),
+        team(info, v),
+        links(info, v),
+        changes(info, v),
+        document(info, "README", info.readme(), &embed("readme"), false),
+        document(info, "LICENSE", info.license_file(), &embed("license"), true)
+    ))
+}
+
+fn write_changelog(info: &ProjectInfo, v: &str) -> bool {
+    let content = match changelog(info, v) {
+        Ok(c) => c,
+        Err(e) => {
+            println!("Cannot generate the change log: {e}");
+            return false;
+        }
+    };
+    if Path::new("./logs").is_dir().eq(&false) {
+        fs::create_dir_all("./logs").expect("msg");
+    }
+    let filename = changelog_path(info.name(), v);
+    fs::write(filename.as_str(), content).expect("failed to create file");
+    Path::new(filename.as_str()).exists()
+}
+
+fn create_changelog(info: &ProjectInfo) -> bool {
+    write_changelog(info, info.version().as_str())
+}
+
+fn config() -> DocumentMut {
+    read_to_string(CONFIG)
+        .unwrap_or_default()
+        .parse::<DocumentMut>()
+        .unwrap_or_else(|e| {
+            static WARNED: Once = Once::new();
+            WARNED.call_once(|| {
+                println!("{CONFIG} is not a valid toml file, using the defaults: {e}")
+            });
+            DocumentMut::new()
+        })
+}
+
+fn setting(table: &str, key: &str) -> Option<String> {
+    config()
+        .get(table)?
+        .get(key)?
+        .as_str()
+        .map(ToString::to_string)
+}
+
+fn number(table: &str, key: &str) -> Option<i64> {
+    let value = config().get(table)?.get(key)?.clone();
+    value
+        .as_integer()
+        .or_else(|| value.as_str()?.trim().parse().ok())
+}
+
+fn enabled(table: &str, key: &str) -> bool {
+    config()
+        .get(table)
+        .and_then(|t| t.get(key))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+fn git_config(key: &str) -> Option<String> {
+    let value = String::from_utf8(
+        Command::new("git")
+            .arg("config")
+            .arg("--get")
+            .arg(key)
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
+    )
+    .expect("msg");
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+fn sign(kind: &str) -> Option<&'static str> {
+    if !enabled("sign", kind) {
+        return None;
+    }
+    let format = git_config("gpg.format").unwrap_or_else(|| String::from("openpgp"));
+    match git_config("user.signingkey") {
+        Some(key) => println!("Signing {kind} with the {format} key {key}"),
+        None if format.eq("openpgp") => {
+            println!("Signing {kind} with the default gpg key of the committer");
+        }
+        None => println!(
+            "gpg.format is {format} but user.signingkey is not set, git will refuse to sign"
+        ),
+    }
+    if kind.eq("tags") {
+        Some("-s")
+    } else {
+        Some("-S")
+    }
+}
+
+enum Embed {
+    Skip,
+    Link,
+    Excerpt,
+    Full,
+}
+
+fn embed(key: &str) -> Embed {
+    match setting("changelog", key).as_deref() {
+        Some("none") => Embed::Skip,
+        Some("excerpt") => Embed::Excerpt,
+        Some("full") => Embed::Full,
+        _ => Embed::Link,
+    }
+}
+
+fn document(
+    info: &ProjectInfo,
+    title: &str,
+    path: Option<String>,
+    mode: &Embed,
+    code: bool,
+) -> String {
+    let Some(path) = path else {
+        return String::new();
+    };
+    if matches!(mode, Embed::Skip) {
+        return String::new();
+    }
+    if matches!(mode, Embed::Link) {
+        return format!(
+            "\n## {title}\n\n- [{path}]({})\n",
+            file_link(info, path.as_str())
+        );
+    }
+    let Ok(content) = read_to_string(path.as_str()) else {
+        println!("{path} not found, skipping the {title} section");
+        return String::new();
+    };
+    let mut body = if matches!(mode, Embed::Excerpt) {
+        content
+            .lines()
+            .take(EXCERPT_LINES)
+            .collect::<Vec<&str>>()
+            .join("\n")
+    } else {
+        content
+    };
+    body = body.trim().to_string();
+    if code {
+        body = format!("```\n{body}\n```");
+    }
+    if matches!(mode, Embed::Excerpt) {
+        body.push_str(format!("\n\n[Read more]({})", file_link(info, path.as_str())).as_str());
+    }
+    format!("\n## {title}\n\n{body}\n")
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ForgeKind {
+    GitHub,
+    GitLab,
+    Gitea,
+    Forgejo,
+    Bitbucket,
+    SourceHut,
+    Unknown,
+}
+
+impl ForgeKind {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "github" => Some(Self::GitHub),
+            "gitlab" => Some(Self::GitLab),
+            "gitea" => Some(Self::Gitea),
+            "forgejo" | "codeberg" => Some(Self::Forgejo),
+            "bitbucket" => Some(Self::Bitbucket),
+            "sourcehut" | "srht" => Some(Self::SourceHut),
+            "unknown" | "generic" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+
+    fn from_host(host: &str) -> Self {
+        let configured = config()
+            .get("forge")
+            .and_then(|f| f.get("hosts"))
+            .and_then(|h| h.get(host))
+            .and_then(|k| k.as_str())
+            .and_then(Self::from_name);
+        if let Some(kind) = configured {
+            return kind;
+        }
+        match host {
+            "github.com" => Self::GitHub,
+            "gitlab.com" => Self::GitLab,
+            "codeberg.org" => Self::Forgejo,
+            "bitbucket.org" => Self::Bitbucket,
+            "git.sr.ht" => Self::SourceHut,
+            h if h.contains("gitlab") => Self::GitLab,
+            h if h.contains("forgejo") => Self::Forgejo,
+            h if h.contains("gitea") => Self::Gitea,
+            h if h.contains("github") => Self::GitHub,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+struct Forge {
+    kind: ForgeKind,
+    url: String,
+}
+
+impl Forge {
+    fn new(repository: &str) -> Self {
+        let url = setting("forge", "url").map_or_else(
+            || normalize_repository(repository),
+            |u| normalize_repository(u.as_str()),
+        );
+        let host = url
+            .split("://")
+            .last()
+            .unwrap_or_default()
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let kind = setting("forge", "type")
+            .and_then(|k| ForgeKind::from_name(k.as_str()))
+            .unwrap_or_else(|| ForgeKind::from_host(host.as_str()));
+        Self { kind, url }
+    }
+
+    fn link(&self, path: &str) -> String {
+        format!("{}{path}", self.url)
+    }
+
+    fn issues(&self) -> String {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::Forgejo | ForgeKind::Bitbucket => {
+                self.link("/issues")
+            }
+            ForgeKind::GitLab => self.link("/-/issues"),
+            ForgeKind::SourceHut => self.url.replace("git.sr.ht", "todo.sr.ht"),
+            ForgeKind::Unknown => self.url.clone(),
+        }
+    }
+
+    fn issue(&self, number: &str) -> Option<String> {
+        if self.kind.eq(&ForgeKind::Unknown) {
+            return None;
+        }
+        Some(format!("{}/{number}", self.issues()))
+    }
+
+    fn commit(&self, hash: &str) -> Option<String> {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::Forgejo | ForgeKind::SourceHut => {
+                Some(self.link(format!("/commit/{hash}").as_str()))
+            }
+            ForgeKind::GitLab => Some(self.link(format!("/-/commit/{hash}").as_str())),
+            ForgeKind::Bitbucket => Some(self.link(format!("/commits/{hash}").as_str())),
+            ForgeKind::Unknown => None,
+        }
+    }
+
+    fn compare(&self, from: &str, to: &str) -> Option<String> {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::Forgejo => {
+                Some(self.link(format!("/compare/{from}...{to}").as_str()))
+            }
+            ForgeKind::GitLab => Some(self.link(format!("/-/compare/{from}...{to}").as_str())),
+            ForgeKind::Bitbucket => {
+                Some(self.link(format!("/branches/compare/{to}%0D{from}").as_str()))
+            }
+            ForgeKind::SourceHut | ForgeKind::Unknown => None,
+        }
+    }
+
+    fn pulls(&self) -> Option<String> {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::Forgejo => Some(self.link("/pulls")),
+            ForgeKind::GitLab => Some(self.link("/-/merge_requests")),
+            ForgeKind::Bitbucket => Some(self.link("/pull-requests")),
+            ForgeKind::SourceHut | ForgeKind::Unknown => None,
+        }
+    }
+
+    fn tag(&self, tag: &str) -> String {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::Forgejo => {
+                self.link(format!("/releases/tag/{tag}").as_str())
+            }
+            ForgeKind::GitLab => self.link(format!("/-/tags/{tag}").as_str()),
+            ForgeKind::Bitbucket => self.link(format!("/src/{tag}").as_str()),
+            ForgeKind::SourceHut => self.link(format!("/refs/{tag}").as_str()),
+            ForgeKind::Unknown => self.url.clone(),
+        }
+    }
+
+    fn releases(&self) -> String {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::Forgejo => self.link("/releases"),
+            ForgeKind::GitLab => self.link("/-/releases"),
+            ForgeKind::Bitbucket => self.link("/downloads/?tab=tags"),
+            ForgeKind::SourceHut => self.link("/refs"),
+            ForgeKind::Unknown => self.url.clone(),
+        }
+    }
+
+    fn wiki(&self) -> Option<String> {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::Forgejo | ForgeKind::Bitbucket => {
+                Some(self.link("/wiki"))
+            }
+            ForgeKind::GitLab => Some(self.link("/-/wikis")),
+            ForgeKind::SourceHut => Some(self.url.replace("git.sr.ht", "man.sr.ht")),
+            ForgeKind::Unknown => None,
+        }
+    }
+
+    fn projects(&self) -> Option<String> {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::Forgejo => {
+                Some(self.link("/projects"))
+            }
+            ForgeKind::GitLab => Some(self.link("/-/boards")),
+            _ => None,
+        }
+    }
+
+    fn discussions(&self) -> Option<String> {
+        match self.kind {
+            ForgeKind::GitHub => Some(self.link("/discussions")),
+            ForgeKind::SourceHut => Some(self.url.replace("git.sr.ht", "lists.sr.ht")),
+            _ => None,
+        }
+    }
+
+    fn file(&self, path: &str) -> String {
+        let path = path.trim_start_matches("./");
+        match self.kind {
+            ForgeKind::GitHub => self.link(format!("/blob/HEAD/{path}").as_str()),
+            ForgeKind::GitLab => self.link(format!("/-/blob/HEAD/{path}").as_str()),
+            ForgeKind::Gitea | ForgeKind::Forgejo => {
+                self.link(format!("/src/branch/{}/{path}", default_branch()).as_str())
+            }
+            ForgeKind::Bitbucket => self.link(format!("/src/HEAD/{path}").as_str()),
+            ForgeKind::SourceHut => self.link(format!("/tree/HEAD/item/{path}").as_str()),
+            ForgeKind::Unknown => self.url.clone(),
+        }
+    }
+}
+
+fn normalize_repository(repository: &str) -> String {
+    let mut x = repository.trim().trim_end_matches('/').to_string();
+    if let Some(stripped) = x.strip_suffix(".git") {
+        x = stripped.to_string();
+    }
+    if let Some(rest) = x.strip_prefix("git@") {
+        x = format!("https://{}", rest.replacen(':', "/", 1));
+    } else if let Some(rest) = x
+        .strip_prefix("ssh://")
+        .or_else(|| x.strip_prefix("git+ssh://"))
+        .or_else(|| x.strip_prefix("git://"))
+    {
+        let rest = rest.split_once('@').map_or(rest, |(_, r)| r);
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = host.split(':').next().unwrap_or(host);
+        x = format!("https://{host}/{path}");
+    } else if let Some((scheme, rest)) = x.split_once("://") {
+        let rest = rest.split_once('@').map_or(rest, |(_, r)| r);
+        x = format!("{scheme}://{rest}");
+    }
+    x
+}
+
+fn trunk() -> Option<String> {
+    if let Some(head) = git_lines(&["symbolic-ref", "--short", "refs/remotes/origin/HEAD"]).first()
+    {
+        return Some(head.trim_start_matches("origin/").to_string());
+    }
+    if let Some(b) = ["main", "master"].into_iter().find(|b| has_branch(b)) {
+        return Some(b.to_string());
+    }
+    if !git_ok(&["rev-parse", "-q", "--verify", "HEAD"]) {
+        if let Some(unborn) = git_lines(&["symbolic-ref", "--short", "HEAD"]).first() {
+            return Some(unborn.clone());
+        }
+    }
+    git_config("init.defaultBranch")
+}
+
+fn default_branch() -> String {
+    trunk().unwrap_or_else(|| String::from("main"))
+}
+
+fn origin() -> String {
+    String::from_utf8(
+        Command::new("git")
+            .arg("remote")
+            .arg("get-url")
+            .arg("origin")
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
+    )
+    .expect("msg")
+    .trim()
+    .to_string()
+}
+
+fn forge(info: &ProjectInfo) -> Forge {
+    Forge::new(info.repository().unwrap_or_else(|_| origin()).as_str())
+}
+
+fn file_link(info: &ProjectInfo, path: &str) -> String {
+    forge(info).file(path)
+}
+
+fn issues(info: &ProjectInfo) -> String {
+    forge(info).issues()
+}
+
+fn wiki(info: &ProjectInfo) -> Option<String> {
+    forge(info).wiki()
+}
+
+fn projects(info: &ProjectInfo) -> Option<String> {
+    forge(info).projects()
+}
+
+fn pulls_request(info: &ProjectInfo) -> Option<String> {
+    forge(info).pulls()
+}
+
+fn discussions(info: &ProjectInfo) -> Option<String> {
+    forge(info).discussions()
+}
+
+fn releases(info: &ProjectInfo) -> String {
+    forge(info).releases()
+}
+
+fn links(info: &ProjectInfo, v: &str) -> String {
+    let mut x: Vec<String> = vec![
+        format!("- [Source code]({})", forge(info).url),
+        format!("- [Issues]({})", issues(info)),
+    ];
+    if let Ok(h) = info.homepage() {
+        x.insert(1, format!("- [Home]({h})"));
+    }
+    if let Some(p) = pulls_request(info) {
+        x.push(format!("- [Pull Request]({p})"));
+    }
+    if let Some(d) = discussions(info) {
+        x.push(format!("- [Discussions]({d})"));
+    }
+    if let Some(w) = wiki(info) {
+        x.push(format!("- [Wiki]({w})"));
+    }
+    if let Some(p) = projects(info) {
+        x.push(format!("- [Projects]({p})"));
+    }
+    x.push(format!("- [Releases]({})", releases(info)));
+    x.push(format!(
+        "- [This release]({})",
+        forge(info).tag(info.tag_name(v).as_str())
+    ));
+    x.push(format!(
+        "- [Crates.io](https://crates.io/crates/{}/{v})",
+        info.name()
+    ));
+    x.join("\n")
+}
+
+fn create_patch() {
+    if Path::new("./patches").exists().eq(&false) {
+        assert!(fs::create_dir_all("./patches").is_ok());
+    }
+    assert!(Command::new("git")
+        .arg("format-patch")
+        .arg("-1")
+        .current_dir("./patches")
+        .spawn()
+        .unwrap()
+        .wait()
+        .unwrap()
+        .success());
+}
+fn create_tag(info: &mut ProjectInfo) {
+    let v: String = choose_version(info);
+    let m: String = Text::new("Enter the tag message : ").prompt().unwrap();
+
+    if m.is_empty() {
+        return create_tag(info);
+    }
+    if breaking_without_major(info, v.as_str()) && !confirm("Create the tag anyway ?", false) {
+        return create_tag(info);
+    }
+    if v.ne(&info.version()) && !inherited_bump_confirmed(info, v.as_str()) {
+        println!("Tag cancelled");
+        return;
+    }
+    if v.ne(&info.version()) && !release_version(info, v.as_str(), &[]) {
+        println!("{} is not tagged", info.tag_name(v.as_str()));
+        return;
+    }
+    assert!(tag(info.tag_name(v.as_str()).as_str(), m.as_str()));
+}
+
+fn create_plain_tag() {
+    let v: String = ask("Enter the tag name : ");
+    let m: String = ask("Enter the tag message : ");
+    assert!(tag(v.as_str(), m.as_str()));
+}
+
+fn tag(name: &str, message: &str) -> bool {
+    Command::new("git")
+        .arg("tag")
+        .arg("-a")
+        .args(sign("tags"))
+        .arg(name)
+        .arg("-m")
+        .arg(message)
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+}
+
+fn check_publish(name: &str, registry: Option<&str>) -> bool {
+    Command::new("cargo")
+        .arg("publish")
+        .arg("--dry-run")
+        .arg("--allow-dirty")
+        .arg("-p")
+        .arg(name)
+        .args(registry.map(|r| format!("--registry={r}")))
+        .current_dir(".")
+        .spawn()
+        .expect("cargo")
+        .wait()
+        .unwrap()
+        .success()
+}
+
+fn release(info: &mut ProjectInfo, dry_run: bool, publish: bool) -> bool {
+    let v = choose_version(info);
+    if breaking_without_major(info, v.as_str()) && !confirm("Release anyway ?", false) {
+        println!("Release cancelled");
+        return true;
+    }
+    if !dry_run && !inherited_bump_confirmed(info, v.as_str()) {
+        println!("Release cancelled");
+        return true;
+    }
+    let name = info.name().to_string();
+    let tag_name = info.tag_name(v.as_str());
+    let notes = changelog_path(info.name(), v.as_str());
+    let message = format!("Launch({tag_name}): Release {name} version {v}");
+    let (publish, registry) = match registry(&info.package) {
+        Ok(r) => (publish, r),
+        Err(e) => {
+            if publish {
+                println!("{name} will be tagged but not published: {e}");
+            }
+            (false, None)
+        }
+    };
+    let target = registry.as_deref().unwrap_or("crates-io");
+    if dry_run {
+        let (path, content) = versioned_manifest(info, v.as_str());
+        let changelog = match changelog(info, v.as_str()) {
+            Ok(c) => c,
+            Err(e) => {
+                println!("Cannot generate the change log: {e}");
+                return false;
+            }
+        };
+        println!("1. Write the release notes\n\n--- {notes} ---\n{changelog}");
+        println!("2. Bump {name} from {} to {v}\n", info.version());
+        let inherited = inherited_members(info);
+        if inherited.len().gt(&1) && inherited.contains(&name) {
+            println!(
+                "{name} inherits the workspace version, {v} also applies to : {}\n",
+                inherited.join(", ")
+            );
+        }
+        println!("--- {path} ---\n{content}");
+        if publish {
+            println!("3. Check the package : cargo publish --dry-run -p {name} on {target}");
+        }
+        println!(
+            "{}. Commit the release : {message}",
+            if publish { 4 } else { 3 }
+        );
+        println!("{}. Create the tag {tag_name}", if publish { 5 } else { 4 });
+        if publish {
+            println!("6. Publish : cargo publish -p {name} on {target}");
+        }
+        println!(
+            "{}. Push the branches and the tags to origin",
+            if publish { 7 } else { 5 }
+        );
+        return true;
+    }
+    println!("1. Write the release notes to {notes}");
+    let previous = backup(std::slice::from_ref(&notes));
+    if !write_changelog(info, v.as_str()) {
+        return false;
+    }
+    println!("2. Bump {name} from {} to {v}", info.version());
+    let Some(files) = prepare_version(info, v.as_str(), previous) else {
+        println!("Cannot bump {name} to {v}");
+        return false;
+    };
+    if publish {
+        println!("3. Check the package on {target}");
+        if !check_publish(name.as_str(), registry.as_deref()) {
+            println!("cargo publish --dry-run failed, nothing was committed");
+            restore(&files);
+            return false;
+        }
+    }
+    println!(
+        "{}. Commit the release : {message}",
+        if publish { 4 } else { 3 }
+    );
+    if !commit_version(info, v.as_str(), &files, std::slice::from_ref(&notes)) {
+        println!("Cannot commit the release");
+        return false;
+    }
+    println!("{}. Create the tag {tag_name}", if publish { 5 } else { 4 });
+    if !tag(tag_name.as_str(), message.as_str()) {
+        println!("Cannot create the tag {tag_name}, the release commit is kept");
+        return false;
+    }
+    if publish {
+        println!("6. Publish {name} {v} on {target}");
+        if !publish_package(name.as_str(), registry.as_deref()) {
+            println!("cargo publish failed, {tag_name} is tagged but not published");
+            return false;
+        }
+    }
+    println!(
+        "{}. Push the branches and the tags to origin",
+        if publish { 7 } else { 5 }
+    );
+    if !send() {
+        println!("Cannot push to origin, run send once the remote is reachable");
+        return false;
+    }
+    true
+}
+
+fn send_tag() {
+    assert!(Command::new("git")
+        .arg("push")
+        .arg("--all")
+        .current_dir(".")
+        .spawn()
+        .unwrap()
+        .wait()
+        .unwrap()
+        .success());
+    assert!(Command::new("git")
+        .arg("push")
+        .arg("--tags")
+        .current_dir(".")
+        .spawn()
+        .unwrap()
+        .wait()
+        .unwrap()
+        .success());
+}
+fn commit(m: &str, info: Option<&mut ProjectInfo>) -> bool {
+    assert!(Command::new("git")
+        .arg("commit")
+        .args(sign("commits"))
+        .arg("-m")
+        .arg(m)
+        .current_dir(".")
+        .spawn()
+        .unwrap()
+        .wait()
+        .unwrap()
+        .success());
+    create_patch();
+    if confirm("Create new tag", false) {
+        match info {
+            Some(info) => {
+                create_tag(info);
+                if info.is_publishable() && confirm("Publish the crate ?", false) {
+                    assert!(publish(info));
+                }
+            }
+            None => create_plain_tag(),
+        }
+        send_tag();
+    }
+    true
+}
+
+fn diff() -> bool {
+    Command::new("git")
+        .arg("diff")
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+}
+
+fn fmt() {
+    assert!(Command::new("cargo")
+        .arg("fmt")
+        .current_dir(".")
+        .spawn()
+        .unwrap()
+        .wait()
+        .unwrap()
+        .success());
+    clear();
+}
+
+fn zuu() -> bool {
+    clear();
+
+    if Path::new("Cargo.toml").exists() {
+        fmt();
+        if Command::new("zuu")
+            .current_dir(".")
+            .spawn()
+            .unwrap()
+            .wait()
+            .unwrap()
+            .success()
+        {
+            clear();
+            return true;
+        }
+        return false;
+    }
+    clear();
+    true
+}
+
+struct ProjectInfo {
+    metadata: Metadata,
+    package: Package,
+}
+
+impl ProjectInfo {
+    fn load(member: Option<String>) -> Option<Self> {
+        let metadata = MetadataCommand::new().no_deps().exec().ok()?;
+        let packages: Vec<Package> = metadata.workspace_packages().into_iter().cloned().collect();
+        let package = if packages.len().eq(&1) {
+            packages[0].clone()
+        } else {
+            let selected = member.or_else(|| setting("workspace", "package"));
+            match selected.and_then(|s| packages.iter().find(|p| p.name.eq(&s))) {
+                Some(p) => p.clone(),
+                None => {
+                    let name = Select::new(
+                        "Select the workspace member : ",
+                        packages.iter().map(|p| p.name.clone()).collect(),
+                    )
+                    .prompt()
+                    .unwrap();
+                    packages.into_iter().find(|p| p.name.eq(&name))?
+                }
+            }
+        };
+        Some(Self { metadata, package })
+    }
+
+    fn reload(&mut self) {
+        if let Some(info) = Self::load(Some(self.package.name.clone())) {
+            *self = info;
+        }
+    }
+
+    fn select(&mut self) -> bool {
+        let member = Select::new("Select the workspace member : ", self.members())
+            .prompt()
+            .unwrap();
+        match Self::load(Some(member)) {
+            Some(info) => {
+                *self = info;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn member(&self, name: &str) -> Option<&Package> {
+        self.metadata
+            .workspace_packages()
+            .into_iter()
+            .find(|p| p.name.eq(name))
+    }
+
+    fn is_publishable(&self) -> bool {
+        registry_allowed(&self.package)
+    }
+
+    fn members(&self) -> Vec<String> {
+        self.metadata
+            .workspace_packages()
+            .iter()
+            .map(|p| p.name.clone())
+            .collect()
+    }
+
+    fn is_workspace(&self) -> bool {
+        self.metadata.workspace_members.len().gt(&1)
+    }
+
+    fn name(&self) -> &str {
+        self.package.name.as_str()
+    }
+
+    fn version(&self) -> String {
+        self.package.version.to_string()
+    }
+
+    fn required(&self, field: &str, value: Option<&String>) -> Result<String, String> {
+        value
+            .cloned()
+            .ok_or_else(|| format!("missing `{field}` in {}", self.package.manifest_path))
+    }
+
+    fn description(&self) -> Result<String, String> {
+        self.required("description", self.package.description.as_ref())
+    }
+
+    fn homepage(&self) -> Result<String, String> {
+        self.required("homepage", self.package.homepage.as_ref())
+    }
+
+    fn repository(&self) -> Result<String, String> {
+        self.required("repository", self.package.repository.as_ref())
+    }
+
+    fn readme(&self) -> Option<String> {
+        self.package
+            .readme
+            .as_ref()
+            .map(|r| self.package_file(r.as_str()))
+    }
+
+    fn license_file(&self) -> Option<String> {
+        self.package
+            .license_file
+            .as_ref()
+            .map(|l| self.package_file(l.as_str()))
+    }
+
+    fn package_file(&self, path: &str) -> String {
+        let file = self
+            .package
+            .manifest_path
+            .parent()
+            .map_or_else(|| path.into(), |dir| dir.join(path));
+        file.strip_prefix(&self.metadata.workspace_root)
+            .map_or_else(|_| file.to_string(), ToString::to_string)
+    }
+
+    fn keywords(&self) -> &[String] {
+        &self.package.keywords
+    }
+
+    fn authors(&self) -> &[String] {
+        &self.package.authors
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.package
+            .dependencies
+            .iter()
+            .map(|dep| dep.name.clone())
+            .collect()
+    }
+
+    fn is_library(&self) -> bool {
+        self.package
+            .targets
+            .iter()
+            .any(|t| t.kind.iter().any(|k| k.contains("lib")))
+    }
+
+    fn manifest_path(&self) -> String {
+        self.package.manifest_path.to_string()
+    }
+
+    fn root_manifest(&self) -> String {
+        format!(
+            "{}{MAIN_SEPARATOR_STR}Cargo.toml",
+            self.metadata.workspace_root
+        )
+    }
+
+    fn lock_file(&self) -> String {
+        format!(
+            "{}{MAIN_SEPARATOR_STR}Cargo.lock",
+            self.metadata.workspace_root
+        )
+    }
+
+    fn member_path(&self) -> Option<String> {
+        if !self.is_workspace() {
+            return None;
+        }
+        let dir = self
+            .package
+            .manifest_path
+            .parent()?
+            .strip_prefix(&self.metadata.workspace_root)
+            .ok()?;
+        if dir.as_str().is_empty() {
+            return None;
+        }
+        Some(dir.to_string())
+    }
+
+    fn tag_prefix(&self) -> String {
+        if self.is_workspace() {
+            format!("{}-v", self.name())
+        } else {
+            String::new()
+        }
+    }
+
+    fn tag_name(&self, v: &str) -> String {
+        format!("{}{v}", self.tag_prefix())
+    }
+
+    fn publish_order(&self) -> Vec<String> {
+        let mut remaining: Vec<&Package> = self.metadata.workspace_packages();
+        let names: HashSet<String> = remaining.iter().map(|p| p.name.clone()).collect();
+        let mut ordered: Vec<String> = Vec::new();
+        while !remaining.is_empty() {
+            let (ready, blocked): (Vec<&Package>, Vec<&Package>) =
+                remaining.into_iter().partition(|p| {
+                    p.dependencies
+                        .iter()
+                        .filter(|d| {
+                            d.kind.ne(&DependencyKind::Development) && names.contains(&d.name)
+                        })
+                        .all(|d| ordered.contains(&d.name))
+                });
+            assert!(
+                !ready.is_empty(),
+                "cyclic dependencies between workspace members"
+            );
+            ordered.extend(ready.iter().map(|p| p.name.clone()));
+            remaining = blocked;
+        }
+        ordered
+    }
+}
+
+fn cargo(info: &mut Option<ProjectInfo>) -> &mut ProjectInfo {
+    info.as_mut().expect("not a cargo project")
+}
+
+fn clear() {
+    if OS.eq("windows") {
+        assert!(Command::new("cls")
+            .current_dir(".")
+            .spawn()
+            .unwrap()
+            .wait()
+            .unwrap()
+            .success());
+    } else {
+        assert!(Command::new("clear")
+            .current_dir(".")
+            .spawn()
+            .unwrap()
+            .wait()
+            .unwrap()
+            .success());
+    }
+}
+
+fn commit_types_with_help() -> [&'static str; 68] {
+    let mut x = HELP;
+    x.sort_unstable();
+    x
+}
+
+fn commit_scope() -> String {
+    let mut scope: String;
+    loop {
+        scope = Text::new("Please enter the commit scope : ")
+            .prompt()
+            .unwrap();
+        if scope.is_empty() {
+            continue;
+        }
+        if scope.len().gt(&20) {
+            println!("scope can be superior to 20 character");
+            continue;
+        }
+        if confirm(
+            format!("Really use the commit scope : {scope}").as_str(),
+            false,
+        ) {
+            break;
+        }
+    }
+    scope
+}
+
+fn commit_types() -> String {
+    let mut t: String;
+    loop {
+        t = Select::new(
+            "Please enter the commit type : ",
+            commit_types_with_help().to_vec(),
+        )
+        .prompt()
+        .unwrap()
+        .to_string();
+        if t.is_empty() {
+            continue;
+        }
+        if confirm(format!("Really use the commit type : {t}").as_str(), false) {
+            break;
+        }
+    }
+    let x: Vec<&str> = t.split(':').collect();
+    (*x.first().unwrap()).to_string()
+}
+
+fn commit_summary() -> String {
+    let mut summary: String;
+    loop {
+        summary = Text::new("Please enter the commit summary : ")
+            .prompt()
+            .unwrap();
+        if summary.is_empty() {
+            continue;
+        }
+        if summary.len().gt(&50) {
+            println!("Summary must be contains less than 50 chararacter");
+            continue;
+        }
+        if confirm(format!("Use the summary : {summary}").as_str(), false) {
+            break;
+        }
+    }
+    summary
+}
+
+fn commit_description() -> String {
+    let mut description: String = String::new();
+    loop {
+        let d = Text::new("Please enter the commit description : ")
+            .prompt()
+            .unwrap();
+        if d.is_empty() {
+            continue;
+        }
+        description.push_str(format!("{d}\n\n").as_str());
+        if confirm("Add a new description line", false) {
+            continue;
+        }
+        break;
+    }
+    description
+}
+
+fn commit_why() -> String {
+    let mut why: String = String::new();
+    loop {
+        let w = Text::new("Please explain the reasoning behind the change : ")
+            .prompt()
+            .unwrap();
+        if w.is_empty() {
+            continue;
+        }
+        if w.len().gt(&50) {
+            println!("The reasoning behind the change must be contains less than 50 chararacter");
+            continue;
+        }
+        why.push_str(format!("\n\t* {w}").as_str());
+        if confirm("Continue to write the changes : ", false) {
+            continue;
+        }
+        break;
+    }
+    why
+}
+fn commit_footer() -> String {
+    let mut footer: String = String::new();
+    if confirm("Code has breaking changes ?", false) {
+        footer.push_str("BREAKING CHANGE: ");
+        loop {
+            let b = Text::new("Please enter the breaking change description: ")
+                .prompt()
+                .unwrap();
+            if b.is_empty() {
+                continue;
+            }
+            if confirm(
+                format!("Use breaking change description : {b}").as_str(),
+                false,
+            ) {
+                footer.push_str(b.as_str());
+                break;
+            }
+        }
+        let m = Text::new("Please enter the migration notes (leave empty to skip) : ")
+            .prompt()
+            .unwrap();
+        if !m.is_empty() {
+            footer.push_str(format!("\nMIGRATION: {m}").as_str());
+        }
+    }
+    if confirm("Code has resolving issues ?", false) {
+        loop {
+            footer.push_str("\n\tFixes ");
+            let f = Text::new("Please enter the issue number : ")
+                .prompt()
+                .unwrap();
+            if f.is_empty() {
+                continue;
+            }
+            footer.push_str(format!("#{f}\n").as_str());
+            if confirm("Code resolving an other issues ?", false) {
+                continue;
+            }
+            break;
+        }
+    }
+    if confirm("Code resolve an issue ?", false) {
+        loop {
+            footer.push_str("\n\tCloses ");
+            let f = Text::new("Please enter the issue number : ")
+                .prompt()
+                .unwrap();
+            if f.is_empty() {
+                continue;
+            }
+            footer.push_str(format!("#{f}\n").as_str());
+            if confirm("Code resolve an other issue ?", false) {
+                continue;
+            }
+            break;
+        }
+    }
+    footer
+}
+
+fn bad_sentences() {
+    println!("The entered text is not correct: it must be written in English and not contain any errors.");
+}
+fn get_scope() -> String {
+    let mut scope: String;
+    loop {
+        scope = commit_scope();
+        if check(scope.as_str()) {
+            break;
+        }
+        bad_sentences();
+    }
+    scope
+}
+
+fn get_summary() -> String {
+    let mut summary: String;
+    loop {
+        summary = commit_summary();
+        if check(summary.as_str()) {
+            break;
+        }
+        bad_sentences();
+    }
+    summary
+}
+
+fn get_description() -> String {
+    let mut description: String;
+    loop {
+        description = commit_description();
+        if check(description.as_str()) {
+            break;
+        }
+        bad_sentences();
+    }
+    description
+}
+
+fn get_why() -> String {
+    let mut why: String;
+    loop {
+        why = commit_why();
+        if check(why.as_str()) {
+            break;
+        }
+        bad_sentences();
+    }
+    why
+}
+fn get_footer() -> String {
+    let mut footer: String;
+    loop {
+        footer = commit_footer();
+        if check(footer.as_str()) {
+            break;
+        }
+        bad_sentences();
+    }
+    footer
+}
+fn prepare_commit(info: Option<&mut ProjectInfo>) -> bool {
+    if git_ok(&["diff", "--cached", "--quiet"]) {
+        println!("Nothing is staged, use \"Add modifications\" to select the changes to commit");
+        return true;
+    }
+    let c = format!(
+        "{}({}): {}\n\n{}\n\nThe following changes were made:\n\t{}\n\nThe changes :\n{}\n\nCo-authored-by: {} <{}>",
+        commit_types(),
+        get_scope(),
+        get_summary(),
+        get_description(),
+        get_why(),
+        get_footer(),
+        name(),
+        email()
+    );
+    commit(c.as_str(), info)
+}
+
+fn confirm(msg: &str, default: bool) -> bool {
+    Confirm::new(msg)
+        .with_default(default)
+        .prompt()
+        .unwrap()
+        .eq(&true)
+}
+
+fn email() -> String {
+    String::from_utf8(
+        Command::new("git")
+            .arg("config")
+            .arg("--get")
+            .arg("user.email")
+            .current_dir(".")
+            .output()
+            .expect("git email not found")
+            .stdout,
+    )
+    .expect("msg")
+    .trim()
+    .to_string()
+}
+
+fn name() -> String {
+    String::from_utf8(
+        Command::new("git")
+            .arg("config")
+            .arg("--get")
+            .arg("user.name")
+            .current_dir(".")
+            .output()
+            .expect("username not found")
+            .stdout,
+    )
+    .expect("msg")
+    .trim()
+    .to_string()
+}
+
+enum Verb {
+    Start,
+    Finish,
+}
+
+fn checkout(b: &str) -> bool {
+    Command::new("git")
+        .arg("checkout")
+        .arg(b)
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+}
+fn create_branch(b: &str) -> bool {
+    Command::new("git")
+        .arg("branch")
+        .arg(b)
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+}
+
+fn remove_branch(b: &str) -> bool {
+    Command::new("git")
+        .arg("branch")
+        .arg("-d")
+        .arg(b)
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+}
+
+struct Change {
+    code: String,
+    path: String,
+}
+
+impl Change {
+    fn untracked(&self) -> bool {
+        self.code.eq("??")
+    }
+
+    fn label(&self) -> String {
+        if self.untracked() {
+            return format!("untracked        | {}", self.path);
+        }
+        let mut codes = self.code.chars();
+        let (x, y) = (codes.next().unwrap_or(' '), codes.next().unwrap_or(' '));
+        let kind = match if x.eq(&' ') { y } else { x } {
+            'D' => "deleted",
+            'A' => "added",
+            'R' => "renamed",
+            'U' => "conflict",
+            _ => "modified",
+        };
+        let state = match (x.eq(&' '), y.eq(&' ')) {
+            (true, _) => "",
+            (false, true) => " staged",
+            (false, false) => " partly",
+        };
+        format!("{:<16} | {}", format!("{kind}{state}"), self.path)
+    }
+}
+
+fn changes_to_stage() -> Vec<Change> {
+    let output = String::from_utf8(
+        Command::new("git")
+            .arg("status")
+            .arg("--porcelain")
+            .arg("-z")
+            .arg("--untracked-files=all")
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
+    )
+    .expect("msg");
+    let mut records = output.split('\0').filter(|r| !r.is_empty());
+    let mut x: Vec<Change> = Vec::new();
+    while let Some(record) = records.next() {
+        let code = record.get(..2).unwrap_or(record);
+        if code.starts_with('R') || code.starts_with('C') {
+            records.next();
+        }
+        x.push(Change {
+            code: code.to_string(),
+            path: record.get(3..).unwrap_or_default().to_string(),
+        });
+    }
+    x
+}
+
+fn preview(change: &Change) -> bool {
+    let mut git = Command::new("git");
+    git.arg("--no-pager").arg("diff");
+    if change.untracked() {
+        git.arg("--no-index").arg("--").arg("/dev/null");
+    } else if git_ok(&["rev-parse", "-q", "--verify", "HEAD"]) {
+        git.arg("HEAD").arg("--");
+    } else if Path::new(change.path.as_str()).exists() {
+        // nothing is committed yet, the whole file is new
+        git.arg("--no-index").arg("--").arg("/dev/null");
+    } else {
+        git.arg("--cached").arg("--");
+    }
+    git.arg(change.path.as_str())
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .is_ok()
+}
+
+fn git_path_command(args: &[&str], path: &str) -> bool {
+    Command::new("git")
+        .args(args)
+        .arg("--")
+        .arg(path)
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+}
+
+fn add() -> bool {
+    loop {
+        let changes = changes_to_stage();
+        if changes.is_empty() {
+            println!("There is no modification to stage");
+            return true;
+        }
+        let mut choices: Vec<String> = changes.iter().map(Change::label).collect();
+        choices.push(String::from("Stage several files"));
+        choices.push(String::from("Done"));
+        let choice = Select::new("Select a file : ", choices).prompt().unwrap();
+        match choice.as_str() {
+            "Done" => return true,
+            "Stage several files" => {
+                let labels: Vec<String> = changes.iter().map(Change::label).collect();
+                let selected = MultiSelect::new("Select the files to stage : ", labels)
+                    .prompt()
+                    .unwrap();
+                for change in changes.iter().filter(|c| selected.contains(&c.label())) {
+                    assert!(git_path_command(&["add"], change.path.as_str()));
+                }
+            }
+            label => {
+                let Some(change) = changes.iter().find(|c| c.label().eq(label)) else {
+                    continue;
+                };
+                match Select::new(
+                    format!("What you want do with {} : ", change.path).as_str(),
+                    vec![
+                        "Show diff",
+                        "Stage the file",
+                        "Stage hunks",
+                        "Unstage",
+                        "Back",
+                    ],
+                )
+                .prompt()
+                .unwrap()
+                {
+                    "Show diff" => {
+                        preview(change);
+                        confirm("Continue ?", true);
+                    }
+                    "Stage the file" => {
+                        assert!(git_path_command(&["add"], change.path.as_str()));
+                    }
+                    "Stage hunks" => {
+                        if change.untracked() {
+                            assert!(git_path_command(&["add", "-N"], change.path.as_str()));
+                        }
+                        git_path_command(&["add", "-p"], change.path.as_str());
+                    }
+                    "Unstage" => {
+                        assert!(git_path_command(
+                            &["reset", "--quiet"],
+                            change.path.as_str()
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn git_ok(args: &[&str]) -> bool {
+    Command::new("git")
+        .args(args)
+        .current_dir(".")
+        .output()
+        .expect("git")
+        .status
+        .success()
+}
+
+fn has_branch(b: &str) -> bool {
+    git_ok(&[
+        "show-ref",
+        "--verify",
+        "--quiet",
+        format!("refs/heads/{b}").as_str(),
+    ])
+}
+
+fn init() -> bool {
+    let main = main_branch().unwrap_or_else(|| {
+        let current = git_lines(&["branch", "--show-current"]);
+        match current.first().filter(|b| b.ne(&DEV_BRANCH)) {
+            Some(b) => Text::new("Enter the main branch : ")
+                .with_default(b)
+                .prompt()
+                .unwrap(),
+            None => String::from("main"),
+        }
+    });
+    if !git_ok(&["rev-parse", "--is-inside-work-tree"])
+        && !Command::new("git")
+            .arg("init")
+            .arg(format!("--initial-branch={main}").as_str())
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
             .unwrap()
+            .success()
+    {
+        return false;
+    }
+    if !Path::new(".gitignore").exists() {
+        fs::write(".gitignore", GITIGNORE).expect("failed to write .gitignore");
+    }
+    if !Path::new(CONFIG).exists() {
+        fs::write(
+            CONFIG,
+            format!("[flow]\nmain = \"{main}\"\nstrategy = \"merge\"\n\n[changelog]\nreadme = \"link\"\nlicense = \"link\"\n\n[sign]\ncommits = false\ntags = false\n"),
+        )
+        .expect("failed to write commiter.toml");
+    }
+    if !git_ok(&["remote", "get-url", "origin"]) && confirm("Add a remote ?", false) {
+        assert!(Command::new("git")
+            .arg("remote")
+            .arg("add")
+            .arg("origin")
+            .arg(ask("Enter the remote url : ").as_str())
+            .current_dir(".")
+            .spawn()
+            .expect("git")
             .wait()
             .unwrap()
             .success());
-    } else {
-        assert!(Command::new("clear")
+    }
+    if !git_ok(&["rev-parse", "-q", "--verify", "HEAD"]) {
+        let committed = Command::new("git")
+            .arg("add")
+            .arg("-A")
             .current_dir(".")
             .spawn()
-            .unwrap()
+            .expect("git")
             .wait()
             .unwrap()
-            .success());
+            .success()
+            && Command::new("git")
+                .arg("commit")
+                .args(sign("commits"))
+                .arg("-m")
+                .arg("Big Bang: Initial project setup")
+                .current_dir(".")
+                .spawn()
+                .expect("git")
+                .wait()
+                .unwrap()
+                .success();
+        if !committed {
+            return false;
+        }
     }
+    if !has_branch(main.as_str()) {
+        let remote = format!("origin/{main}");
+        let created = if git_ok(&["rev-parse", "-q", "--verify", remote.as_str()]) {
+            git_ok(&["branch", "--track", main.as_str(), remote.as_str()])
+        } else {
+            create_branch(main.as_str())
+        };
+        if !created {
+            return false;
+        }
+    }
+    if !has_branch(DEV_BRANCH) && !create_branch(DEV_BRANCH) {
+        return false;
+    }
+    checkout(DEV_BRANCH)
 }
 
-fn commit_types_with_help() -> [&'static str; 68] {
-    let mut x = HELP;
-    x.sort_unstable();
-    x
+fn merge(branch: &str) -> bool {
+    Command::new("git")
+        .arg("merge")
+        .args(sign("commits"))
+        .arg(branch)
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+}
+fn start_feature(name: &str) -> bool {
+    Command::new("git")
+        .arg("checkout")
+        .arg("-b")
+        .arg(format!("{FEATURE_BRANCH_PREFIX}/{name}").as_str())
+        .arg(DEV_BRANCH)
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+}
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Strategy {
+    Merge,
+    NoFf,
+    Squash,
+    Rebase,
 }
 
-fn commit_scope() -> String {
-    let mut scope: String;
-    loop {
-        scope = Text::new("Please enter the commit scope : ")
-            .prompt()
-            .unwrap();
-        if scope.is_empty() {
-            continue;
-        }
-        if scope.len().gt(&20) {
-            println!("scope can be superior to 20 character");
-            continue;
-        }
-        if confirm(
-            format!("Really use the commit scope : {scope}").as_str(),
-            false,
-        ) {
-            break;
+impl Strategy {
+    const ALL: [Self; 4] = [Self::Merge, Self::NoFf, Self::Squash, Self::Rebase];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::NoFf => "no-ff",
+            Self::Squash => "squash",
+            Self::Rebase => "rebase",
         }
     }
-    scope
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.name().eq(name))
+    }
 }
 
-fn commit_types() -> String {
-    let mut t: String;
-    loop {
-        t = Select::new(
-            "Please enter the commit type : ",
-            commit_types_with_help().to_vec(),
-        )
+fn choose_strategy() -> Strategy {
+    let configured = setting("flow", "strategy")
+        .and_then(|s| Strategy::from_name(s.as_str()))
+        .unwrap_or(Strategy::Merge);
+    let names: Vec<&str> = Strategy::ALL.iter().map(|s| s.name()).collect();
+    let cursor = Strategy::ALL
+        .iter()
+        .position(|s| s.eq(&configured))
+        .unwrap_or(0);
+    Strategy::from_name(
+        Select::new("Select the merge strategy : ", names)
+            .with_starting_cursor(cursor)
+            .prompt()
+            .unwrap(),
+    )
+    .expect("msg")
+}
+
+fn squash_message(branch: &str, name: &str) -> String {
+    let squashed: Vec<Commit> = String::from_utf8(
+        Command::new("git")
+            .arg("log")
+            .arg("--reverse")
+            .arg("--format=%H%x1f%s%x1f%b%x1e")
+            .arg(format!("{DEV_BRANCH}..{branch}").as_str())
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
+    )
+    .expect("msg")
+    .split('\x1e')
+    .filter_map(Commit::parse)
+    .collect();
+    let kinds: HashSet<&str> = squashed.iter().map(|c| c.kind.as_str()).collect();
+    let kind = match kinds.iter().next() {
+        Some(k) if kinds.len().eq(&1) && !k.is_empty() => (*k).to_string(),
+        _ => String::from("Globular Cluster"),
+    };
+    let default = match squashed.as_slice() {
+        [c] => c.summary.clone(),
+        _ => format!("Squash {branch}"),
+    };
+    let summary = Text::new("Enter the summary of the squashed commit : ")
+        .with_default(default.as_str())
         .prompt()
-        .unwrap()
-        .to_string();
-        if t.is_empty() {
-            continue;
+        .unwrap();
+    let breaking: Vec<String> = squashed.iter().filter_map(Commit::breaking).collect();
+    let bang = if breaking.is_empty() { "" } else { "!" };
+    let mut x = format!("{kind}({name}){bang}: {summary}\n\n");
+    for c in &squashed {
+        if c.scope.is_empty() {
+            x.push_str(format!("- {}: {}\n", c.kind, c.summary).as_str());
+        } else {
+            x.push_str(format!("- {}({}): {}\n", c.kind, c.scope, c.summary).as_str());
         }
-        if confirm(format!("Really use the commit type : {t}").as_str(), false) {
-            break;
+    }
+    let mut footers: Vec<String> = breaking
+        .iter()
+        .map(|b| format!("BREAKING CHANGE: {b}"))
+        .collect();
+    footers.extend(
+        squashed
+            .iter()
+            .filter_map(Commit::migration)
+            .map(|m| format!("MIGRATION: {m}")),
+    );
+    for c in &squashed {
+        for (keyword, issue) in c.references() {
+            let mut keyword = keyword;
+            keyword[..1].make_ascii_uppercase();
+            footers.push(format!("{keyword} #{issue}"));
         }
     }
-    let x: Vec<&str> = t.split(':').collect();
-    (*x.first().unwrap()).to_string()
+    if !footers.is_empty() {
+        x.push_str(format!("\n{}\n", footers.join("\n")).as_str());
+    }
+    x
 }
 
-fn commit_summary() -> String {
-    let mut summary: String;
-    loop {
-        summary = Text::new("Please enter the commit summary : ")
-            .prompt()
-            .unwrap();
-        if summary.is_empty() {
-            continue;
-        }
-        if summary.len().gt(&50) {
-            println!("Summary must be contains less than 50 chararacter");
-            continue;
+fn finish_feature(name: &str) -> bool {
+    let branch = format!("{FEATURE_BRANCH_PREFIX}/{name}");
+    let strategy = choose_strategy();
+    if !checkout(DEV_BRANCH) {
+        println!("Cannot switch to {DEV_BRANCH}, {branch} is kept");
+        return true;
+    }
+    if !pull(DEV_BRANCH) {
+        println!("Cannot pull {DEV_BRANCH} from origin, merging into the local branch");
+    }
+    let merged = match strategy {
+        Strategy::Merge => merge(branch.as_str()),
+        Strategy::NoFf => merge_no_ff(branch.as_str(), DEV_BRANCH),
+        Strategy::Squash => {
+            let message = squash_message(branch.as_str(), name);
+            let merged = Command::new("git")
+                .arg("merge")
+                .arg("--squash")
+                .arg(branch.as_str())
+                .current_dir(".")
+                .spawn()
+                .expect("git")
+                .wait()
+                .unwrap()
+                .success();
+            fs::write(git_path("SQUASH_MSG"), message).expect("failed to write SQUASH_MSG");
+            merged
         }
-        if confirm(format!("Use the summary : {summary}").as_str(), false) {
-            break;
+        Strategy::Rebase => Command::new("git")
+            .arg("rebase")
+            .args(sign("commits").map(|_| "--gpg-sign"))
+            .arg(DEV_BRANCH)
+            .arg(branch.as_str())
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success(),
+    };
+    if merged {
+        conclude(branch.as_str(), strategy);
+    } else if conflicts().is_empty() {
+        if in_progress(strategy) && !abort_finish(strategy) {
+            println!("Cannot roll back the merge, check git status");
         }
+        println!("The merge of {branch} failed, {branch} is kept");
+    } else {
+        resolve_conflicts(branch.as_str(), strategy);
     }
-    summary
+    true
 }
 
-fn commit_description() -> String {
-    let mut description: String = String::new();
-    loop {
-        let d = Text::new("Please enter the commit description : ")
-            .prompt()
-            .unwrap();
-        if d.is_empty() {
-            continue;
+fn conclude(branch: &str, strategy: Strategy) -> bool {
+    clear_finish();
+    match strategy {
+        Strategy::Merge | Strategy::NoFf => remove_branch(branch),
+        Strategy::Squash => {
+            let committed = Command::new("git")
+                .arg("commit")
+                .arg("--no-edit")
+                .args(sign("commits"))
+                .current_dir(".")
+                .spawn()
+                .expect("git")
+                .wait()
+                .unwrap()
+                .success();
+            if !committed {
+                pause_finish(branch, strategy);
+                println!("The squash commit failed, {branch} is kept; fix the commit then run \"Finish a feature\" again to resume");
+                return false;
+            }
+            Command::new("git")
+                .arg("branch")
+                .arg("-D")
+                .arg(branch)
+                .current_dir(".")
+                .spawn()
+                .expect("git")
+                .wait()
+                .unwrap()
+                .success()
         }
-        description.push_str(format!("{d}\n\n").as_str());
-        if confirm("Add a new description line", false) {
-            continue;
+        Strategy::Rebase => {
+            checkout(DEV_BRANCH)
+                && Command::new("git")
+                    .arg("merge")
+                    .arg("--ff-only")
+                    .arg(branch)
+                    .current_dir(".")
+                    .spawn()
+                    .expect("git")
+                    .wait()
+                    .unwrap()
+                    .success()
+                && remove_branch(branch)
         }
-        break;
     }
-    description
 }
 
-fn commit_why() -> String {
-    let mut why: String = String::new();
-    loop {
-        let w = Text::new("Please explain the reasoning behind the change : ")
-            .prompt()
-            .unwrap();
-        if w.is_empty() {
-            continue;
-        }
-        if w.len().gt(&50) {
-            println!("The reasoning behind the change must be contains less than 50 chararacter");
-            continue;
-        }
-        why.push_str(format!("\n\t* {w}").as_str());
-        if confirm("Continue to write the changes : ", false) {
-            continue;
+fn conflicts() -> Vec<String> {
+    git_lines(&["diff", "--name-only", "--diff-filter=U"])
+}
+
+fn git_path(name: &str) -> String {
+    git_lines(&["rev-parse", "--git-path", name])
+        .first()
+        .cloned()
+        .expect("not a git repository")
+}
+
+fn in_progress(strategy: Strategy) -> bool {
+    match strategy {
+        Strategy::Rebase => {
+            Path::new(git_path("rebase-merge").as_str()).exists()
+                || Path::new(git_path("rebase-apply").as_str()).exists()
         }
-        break;
+        Strategy::Squash => Path::new(git_path("SQUASH_MSG").as_str()).exists(),
+        _ => Path::new(git_path("MERGE_HEAD").as_str()).exists(),
     }
-    why
 }
-fn commit_footer() -> String {
-    let mut footer: String = String::new();
-    if confirm("Code has breaking changes ?", false) {
-        footer.push_str("BREAKING CHANGE: ");
-        loop {
-            let b = Text::new("Please enter the breaking change description: ")
-                .prompt()
-                .unwrap();
-            if b.is_empty() {
-                continue;
-            }
-            if confirm(
-                format!("Use breaking change description : {b}").as_str(),
-                false,
-            ) {
-                footer.push_str(b.as_str());
-                break;
-            }
-        }
+
+fn paused_finish() -> Option<(String, Strategy)> {
+    let state = read_to_string(git_path("commiter-finish")).ok()?;
+    let (branch, strategy) = state.trim().split_once(' ')?;
+    Some((branch.to_string(), Strategy::from_name(strategy)?))
+}
+
+fn pause_finish(branch: &str, strategy: Strategy) {
+    fs::write(
+        git_path("commiter-finish"),
+        format!("{branch} {}", strategy.name()),
+    )
+    .expect("failed to save the merge state");
+}
+
+fn abort_finish(strategy: Strategy) -> bool {
+    clear_finish();
+    let aborted = if strategy.eq(&Strategy::Rebase) {
+        Command::new("git")
+            .arg("rebase")
+            .arg("--abort")
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success()
+            && checkout(DEV_BRANCH)
+    } else {
+        Command::new("git")
+            .arg("reset")
+            .arg("--merge")
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success()
+    };
+    if strategy.eq(&Strategy::Squash) {
+        let _ = fs::remove_file(git_path("SQUASH_MSG"));
     }
-    if confirm("Code has resolving issues ?", false) {
-        loop {
-            footer.push_str("\n\tFixes ");
-            let f = Text::new("Please enter the issue number : ")
-                .prompt()
-                .unwrap();
-            if f.is_empty() {
-                continue;
-            }
-            footer.push_str(format!("#{f}\n").as_str());
-            if confirm("Code resolving an other issues ?", false) {
-                continue;
-            }
-            break;
-        }
+    aborted
+}
+
+fn clear_finish() {
+    let _ = fs::remove_file(git_path("commiter-finish"));
+}
+
+fn resume_finish(branch: &str, strategy: Strategy) -> bool {
+    if in_progress(strategy) {
+        return resolve_conflicts(branch, strategy);
     }
-    if confirm("Code resolve an issue ?", false) {
-        loop {
-            footer.push_str("\n\tCloses ");
-            let f = Text::new("Please enter the issue number : ")
-                .prompt()
-                .unwrap();
-            if f.is_empty() {
-                continue;
-            }
-            footer.push_str(format!("#{f}\n").as_str());
-            if confirm("Code resolve an other issue ?", false) {
-                continue;
-            }
-            break;
-        }
+    clear_finish();
+    let merged = Command::new("git")
+        .arg("merge-base")
+        .arg("--is-ancestor")
+        .arg(branch)
+        .arg(DEV_BRANCH)
+        .current_dir(".")
+        .output()
+        .expect("git")
+        .status
+        .success();
+    if merged {
+        return remove_branch(branch);
     }
-    footer
+    println!("The merge of {branch} is no longer in progress, {branch} is kept");
+    true
 }
 
-fn bad_sentences() {
-    println!("The entered text is not correct: it must be written in English and not contain any errors.");
-}
-fn get_scope() -> String {
-    let mut scope: String;
+fn resolve_conflicts(branch: &str, strategy: Strategy) -> bool {
     loop {
-        scope = commit_scope();
-        if check(scope.as_str()) {
-            break;
+        let files = conflicts();
+        if files.is_empty() {
+            if strategy.ne(&Strategy::Rebase) {
+                if strategy.ne(&Strategy::Squash)
+                    && !Command::new("git")
+                        .arg("commit")
+                        .arg("--no-edit")
+                        .args(sign("commits"))
+                        .current_dir(".")
+                        .spawn()
+                        .expect("git")
+                        .wait()
+                        .unwrap()
+                        .success()
+                {
+                    pause_finish(branch, strategy);
+                    println!("The merge commit failed, {branch} is kept; fix the commit then run \"Finish a feature\" again to resume");
+                    return false;
+                }
+                return conclude(branch, strategy);
+            }
+            let continued = Command::new("git")
+                .arg("-c")
+                .arg("core.editor=true")
+                .arg("rebase")
+                .arg("--continue")
+                .current_dir(".")
+                .spawn()
+                .expect("git")
+                .wait()
+                .unwrap()
+                .success();
+            if continued {
+                return conclude(branch, strategy);
+            }
+            if conflicts().is_empty() {
+                println!("The rebase of {branch} failed, {branch} is kept");
+                return false;
+            }
+            continue;
+        }
+        println!("Conflicts while merging {branch} into {DEV_BRANCH} :\n");
+        for f in &files {
+            println!("- {f}");
+        }
+        match Select::new(
+            "What you want do : ",
+            vec![
+                "Open the mergetool",
+                "Abort the merge",
+                "Pause and resume later",
+            ],
+        )
+        .prompt()
+        .unwrap()
+        {
+            "Open the mergetool" => {
+                let _ = Command::new("git")
+                    .arg("mergetool")
+                    .current_dir(".")
+                    .spawn()
+                    .expect("git")
+                    .wait();
+            }
+            "Abort the merge" => return abort_finish(strategy),
+            _ => {
+                pause_finish(branch, strategy);
+                println!("Resolve the conflicts then run \"Finish a feature\" again to resume");
+                return true;
+            }
         }
-        bad_sentences();
     }
-    scope
 }
 
-fn get_summary() -> String {
-    let mut summary: String;
-    loop {
-        summary = commit_summary();
-        if check(summary.as_str()) {
-            break;
-        }
-        bad_sentences();
-    }
-    summary
+fn main_branch() -> Option<String> {
+    setting("flow", "main").or_else(trunk)
 }
 
-fn get_description() -> String {
-    let mut description: String;
-    loop {
-        description = commit_description();
-        if check(description.as_str()) {
-            break;
-        }
-        bad_sentences();
+fn branches(prefix: &str) -> Vec<String> {
+    git_lines(&[
+        "branch",
+        "--list",
+        "--format=%(refname:short)",
+        format!("{prefix}/*").as_str(),
+    ])
+}
+
+fn merge_no_ff(branch: &str, into: &str) -> bool {
+    checkout(into)
+        && Command::new("git")
+            .arg("merge")
+            .arg("--no-ff")
+            .args(sign("commits"))
+            .arg("-m")
+            .arg(format!("Wormhole: Merge {branch} into {into}").as_str())
+            .arg(branch)
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success()
+}
+
+fn branch_version(info: &ProjectInfo, branch: &str) -> Option<Version> {
+    let root = git_lines(&["rev-parse", "--show-toplevel"])
+        .first()?
+        .clone();
+    let read = |path: String| -> Option<DocumentMut> {
+        let relative = path
+            .strip_prefix(root.as_str())?
+            .trim_start_matches(MAIN_SEPARATOR_STR);
+        git_lines(&["show", format!("{branch}:{relative}").as_str()])
+            .join("\n")
+            .parse::<DocumentMut>()
+            .ok()
+    };
+    let member = read(info.manifest_path())?;
+    let version = member.get("package")?.get("version")?;
+    let version = if version.get("workspace").is_some() {
+        read(info.root_manifest())?
+            .get("workspace")?
+            .get("package")?
+            .get("version")?
+            .as_str()?
+            .to_string()
+    } else {
+        version.as_str()?.to_string()
+    };
+    Version::parse(version.as_str()).ok()
+}
+
+fn merge_flow(branch: &str, into: &str) -> bool {
+    if merge_no_ff(branch, into) {
+        return true;
     }
-    description
+    let files = conflicts();
+    if files.is_empty() {
+        println!("The merge of {branch} into {into} failed");
+        return false;
+    }
+    println!("Conflicts while merging {branch} into {into} :\n");
+    for f in &files {
+        println!("- {f}");
+    }
+    assert!(Command::new("git")
+        .arg("reset")
+        .arg("--merge")
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success());
+    println!("The merge was aborted");
+    false
 }
 
-fn get_why() -> String {
-    let mut why: String;
-    loop {
-        why = commit_why();
-        if check(why.as_str()) {
-            break;
+fn flow_main() -> Option<String> {
+    match main_branch() {
+        Some(main) if has_branch(main.as_str()) => Some(main),
+        Some(main) => {
+            println!("The main branch {main} does not exist, run \"Init a repository\" or set [flow] main in {CONFIG}");
+            None
+        }
+        None => {
+            println!("Cannot find the main branch, set [flow] main in {CONFIG}");
+            None
         }
-        bad_sentences();
     }
-    why
 }
-fn get_footer() -> String {
-    let mut footer: String;
-    loop {
-        footer = commit_footer();
-        if check(footer.as_str()) {
-            break;
+
+fn flow_version(info: Option<&ProjectInfo>, prefix: &str) -> String {
+    let v = match info {
+        Some(info) if prefix.eq(RELEASE_BRANCH_PREFIX) => choose_version(info),
+        Some(info) => {
+            let current = main_branch()
+                .and_then(|main| branch_version(info, main.as_str()))
+                .unwrap_or_else(|| {
+                    println!(
+                        "Cannot read the version of the main branch, using the current version"
+                    );
+                    parse_version(info, info.version().as_str())
+                        .expect("invalid version in Cargo.toml")
+                });
+            Text::new("Enter the hotfix version : ")
+                .with_default(bump_version(&current, Bump::Patch).to_string().as_str())
+                .prompt()
+                .unwrap()
         }
-        bad_sentences();
+        None => ask(format!("Enter the {prefix} version : ").as_str()),
+    };
+    if Version::parse(v.as_str()).is_err() {
+        println!("{v} is not a valid semantic version");
+        return flow_version(info, prefix);
     }
-    footer
-}
-fn prepare_commit() -> bool {
-    let c = format!(
-        "{}({}): {}\n\n{}\n\nThe following changes were made:\n\t{}\n\nThe changes :\n{}\n\nCo-authored-by: {} <{}>",
-        commit_types(),
-        get_scope(),
-        get_summary(),
-        get_description(),
-        get_why(),
-        get_footer(),
-        name(),
-        email()
-    );
-    commit(c.as_str())
+    v
 }
 
-fn confirm(msg: &str, default: bool) -> bool {
-    Confirm::new(msg)
-        .with_default(default)
-        .prompt()
+fn start_flow(prefix: &str, info: Option<&mut ProjectInfo>) -> bool {
+    let base = if prefix.eq(HOTFIX_BRANCH_PREFIX) {
+        let Some(main) = flow_main() else {
+            return true;
+        };
+        main
+    } else {
+        DEV_BRANCH.to_string()
+    };
+    let v = flow_version(info.as_deref(), prefix);
+    if info
+        .as_deref()
+        .is_some_and(|i| !inherited_bump_confirmed(i, v.as_str()))
+    {
+        println!("The {prefix} is not started");
+        return true;
+    }
+    let started = Command::new("git")
+        .arg("checkout")
+        .arg("-b")
+        .arg(format!("{prefix}/{v}").as_str())
+        .arg(base.as_str())
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
         .unwrap()
-        .eq(&true)
+        .success();
+    if !started {
+        println!("Cannot create {prefix}/{v} from {base}");
+        return true;
+    }
+    if let Some(info) = info {
+        if !release_version(info, v.as_str(), &[]) {
+            println!(
+                "{prefix}/{v} is created but {} stays at {}",
+                info.name(),
+                info.version()
+            );
+        }
+    }
+    true
 }
 
-fn email() -> String {
-    String::from_utf8(
-        Command::new("git")
-            .arg("config")
-            .arg("--get")
-            .arg("user.email")
-            .current_dir(".")
-            .output()
-            .expect("git email not found")
-            .stdout,
+fn finish_flow(prefix: &str, info: Option<&ProjectInfo>) -> bool {
+    let candidates = branches(prefix);
+    if candidates.is_empty() {
+        println!("There is no {prefix} branch to finish");
+        return true;
+    }
+    let branch = Select::new(
+        format!("Select the {prefix} to finish : ").as_str(),
+        candidates,
     )
-    .expect("msg")
-    .trim()
-    .to_string()
+    .prompt()
+    .unwrap();
+    let v = branch
+        .strip_prefix(format!("{prefix}/").as_str())
+        .unwrap_or(branch.as_str())
+        .to_string();
+    let tag_name = info.map_or_else(|| v.clone(), |i| i.tag_name(v.as_str()));
+    let name = info.map_or("", ProjectInfo::name);
+    let back = if prefix.eq(HOTFIX_BRANCH_PREFIX) {
+        branches(RELEASE_BRANCH_PREFIX)
+            .first()
+            .cloned()
+            .unwrap_or_else(|| DEV_BRANCH.to_string())
+    } else {
+        DEV_BRANCH.to_string()
+    };
+    let Some(main) = flow_main() else {
+        return true;
+    };
+    if !merge_flow(branch.as_str(), main.as_str()) {
+        println!(
+            "Merge {main} into {branch}, resolve the conflicts then finish the {prefix} again"
+        );
+        return checkout(branch.as_str());
+    }
+    if !local_tags().contains(&tag_name)
+        && !tag(
+            tag_name.as_str(),
+            format!("Launch({tag_name}): Release {name} version {v}").as_str(),
+        )
+    {
+        println!("Cannot create the tag {tag_name}, {branch} is kept");
+        return checkout(branch.as_str());
+    }
+    if !merge_flow(branch.as_str(), back.as_str()) {
+        println!(
+            "{tag_name} is tagged on {main}, merge {branch} into {back} by hand then finish the {prefix} again to delete it"
+        );
+        return checkout(branch.as_str());
+    }
+    remove_branch(branch.as_str())
 }
 
-fn name() -> String {
+fn git_lines(args: &[&str]) -> Vec<String> {
     String::from_utf8(
         Command::new("git")
-            .arg("config")
-            .arg("--get")
-            .arg("user.name")
+            .args(args)
             .current_dir(".")
             .output()
-            .expect("username not found")
+            .expect("git")
             .stdout,
     )
     .expect("msg")
-    .trim()
-    .to_string()
+    .lines()
+    .map(ToString::to_string)
+    .collect()
 }
 
-enum Verb {
-    Start,
-    Finish,
+fn ahead_behind(branch: &str, base: &str) -> (usize, usize) {
+    let counts = git_lines(&[
+        "rev-list",
+        "--left-right",
+        "--count",
+        format!("{base}...{branch}").as_str(),
+    ]);
+    let counts: Vec<usize> = counts
+        .first()
+        .map(|l| {
+            l.split_whitespace()
+                .filter_map(|c| c.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    (
+        counts.get(1).copied().unwrap_or(0),
+        counts.first().copied().unwrap_or(0),
+    )
 }
 
-fn checkout(b: &str) -> bool {
-    Command::new("git")
-        .arg("checkout")
-        .arg(b)
-        .current_dir(".")
-        .spawn()
-        .expect("git")
-        .wait()
-        .unwrap()
-        .success()
-}
-fn create_branch(b: &str) -> bool {
-    Command::new("git")
-        .arg("branch")
-        .arg(b)
-        .current_dir(".")
-        .spawn()
-        .expect("git")
-        .wait()
-        .unwrap()
-        .success()
+fn feature_branches() -> Vec<String> {
+    let local = git_lines(&[
+        "for-each-ref",
+        "--format=%(refname:short)",
+        format!("refs/heads/{FEATURE_BRANCH_PREFIX}/").as_str(),
+    ]);
+    let remote: Vec<String> = git_lines(&[
+        "for-each-ref",
+        "--format=%(refname:short)",
+        format!("refs/remotes/origin/{FEATURE_BRANCH_PREFIX}/").as_str(),
+    ])
+    .into_iter()
+    .filter(|r| !local.iter().any(|l| r.eq(&format!("origin/{l}"))))
+    .collect();
+    local.into_iter().chain(remote).collect()
 }
 
-fn remove_branch(b: &str) -> bool {
-    Command::new("git")
-        .arg("branch")
-        .arg("-d")
-        .arg(b)
+fn choose_feature() -> Option<String> {
+    let candidates = feature_branches();
+    if candidates.is_empty() {
+        println!("There is no {FEATURE_BRANCH_PREFIX} branch to finish");
+        return None;
+    }
+    if !has_branch(DEV_BRANCH) {
+        println!("{DEV_BRANCH} does not exist, run \"Init a repository\" first");
+        return None;
+    }
+    let labels: Vec<String> = candidates
+        .iter()
+        .map(|b| {
+            let (ahead, behind) = ahead_behind(b, DEV_BRANCH);
+            let last = git_lines(&["log", "-1", "--format=%cr | %an | %s", b.as_str()]);
+            format!(
+                "{b} | {} | {ahead} ahead, {behind} behind {DEV_BRANCH}",
+                last.first().cloned().unwrap_or_default()
+            )
+        })
+        .collect();
+    let choice = Select::new("Select the feature to finish : ", labels)
+        .prompt()
+        .unwrap();
+    let branch = choice.split(" | ").next().unwrap_or_default().to_string();
+    println!("Commits to merge into {DEV_BRANCH} :\n");
+    let shown = Command::new("git")
+        .arg("--no-pager")
+        .arg("log")
+        .arg("--oneline")
+        .arg(format!("{DEV_BRANCH}..{branch}").as_str())
         .current_dir(".")
         .spawn()
         .expect("git")
         .wait()
         .unwrap()
         .success()
+        && Command::new("git")
+            .arg("--no-pager")
+            .arg("diff")
+            .arg("--stat")
+            .arg(format!("{DEV_BRANCH}...{branch}").as_str())
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success();
+    if !shown {
+        println!("Cannot compare {branch} with {DEV_BRANCH}, fetch origin and try again");
+        return None;
+    }
+    if !confirm(format!("Finish {branch} ?").as_str(), true) {
+        return None;
+    }
+    let local = branch.strip_prefix("origin/").unwrap_or(branch.as_str());
+    if local.ne(&branch)
+        && !Command::new("git")
+            .arg("branch")
+            .arg("--track")
+            .arg(local)
+            .arg(branch.as_str())
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success()
+    {
+        println!("Cannot create {local} from {branch}");
+        return None;
+    }
+    local
+        .strip_prefix(format!("{FEATURE_BRANCH_PREFIX}/").as_str())
+        .map(ToString::to_string)
 }
 
-fn add() -> bool {
-    Command::new("git")
-        .arg("add")
-        .arg(".")
-        .current_dir(".")
-        .spawn()
-        .expect("git")
-        .wait()
-        .unwrap()
-        .success()
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
 }
 
-fn init() -> bool {
-    create_branch(DEV_BRANCH) && checkout(DEV_BRANCH)
+fn valid_branch(name: &str) -> bool {
+    git_ok(&["check-ref-format", "--branch", name])
 }
 
-fn merge(branch: &str) -> bool {
-    Command::new("git")
-        .arg("merge")
-        .arg(branch)
-        .current_dir(".")
-        .spawn()
-        .expect("git")
-        .wait()
-        .unwrap()
-        .success()
-}
-fn start_feature(name: &str) -> bool {
-    Command::new("git")
-        .arg("checkout")
-        .arg("-b")
-        .arg(format!("{FEATURE_BRANCH_PREFIX}/{name}").as_str())
-        .arg(DEV_BRANCH)
-        .current_dir(".")
-        .spawn()
-        .expect("git")
-        .wait()
-        .unwrap()
-        .success()
-}
-fn finish_feature(name: &str) -> bool {
-    assert!(checkout(DEV_BRANCH));
-    assert!(pull(DEV_BRANCH));
-    assert!(merge(format!("{FEATURE_BRANCH_PREFIX}/{name}").as_str()));
-    assert!(remove_branch(
-        format!("{FEATURE_BRANCH_PREFIX}/{name}").as_str()
-    ));
-    true
+fn feature_name() -> String {
+    let default = format!("{FEATURE_BRANCH_PREFIX}/<slug>");
+    let pattern = setting("flow", "feature").unwrap_or_else(|| default.clone());
+    let pattern = match pattern.strip_prefix(format!("{FEATURE_BRANCH_PREFIX}/").as_str()) {
+        Some(p) if p.contains("<slug>") => p.to_string(),
+        _ => {
+            println!("The feature pattern {pattern} must start with {FEATURE_BRANCH_PREFIX}/ and contain <slug>, using {default}");
+            String::from("<slug>")
+        }
+    };
+    let slug = slugify(ask("Enter the feature name : ").as_str());
+    let ticket: String = if pattern.contains("<ticket>") {
+        ask("Enter the ticket : ")
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || c.eq(&'-'))
+            .collect::<String>()
+            .trim_matches('-')
+            .to_string()
+    } else {
+        String::new()
+    };
+    let name = pattern
+        .replace("<ticket>", ticket.as_str())
+        .replace("<slug>", slug.as_str());
+    let branch = format!("{FEATURE_BRANCH_PREFIX}/{name}");
+    if slug.is_empty()
+        || (ticket.is_empty() && pattern.contains("<ticket>"))
+        || !valid_branch(branch.as_str())
+    {
+        println!("{branch} is not a valid branch name");
+        return feature_name();
+    }
+    if has_branch(branch.as_str()) {
+        println!("{branch} already exists");
+        return feature_name();
+    }
+    println!("Branch : {branch}");
+    name
 }
 
 fn ask(msg: &str) -> String {
@@ -888,6 +3350,7 @@ fn feature(name: &str, v: &Verb) -> bool {
 fn pull(branch: &str) -> bool {
     Command::new("git")
         .arg("pull")
+        .args(sign("commits"))
         .arg("origin")
         .arg(branch)
         .current_dir(".")
@@ -898,8 +3361,13 @@ fn pull(branch: &str) -> bool {
         .success()
 }
 fn stash() -> bool {
+    let message = ask("Enter the stash message : ");
     Command::new("git")
         .arg("stash")
+        .arg("push")
+        .args(confirm("Include untracked files ?", true).then_some("--include-untracked"))
+        .arg("-m")
+        .arg(message.as_str())
         .current_dir(".")
         .spawn()
         .expect("git")
@@ -943,12 +3411,12 @@ fn update() -> bool {
         .success()
 }
 
-fn remove_dependencies() -> bool {
-    let dependencies = MultiSelect::new("Select dependencies to remove : ", dependencies())
+fn remove_dependencies(info: &ProjectInfo) -> bool {
+    let dependencies = MultiSelect::new("Select dependencies to remove : ", info.dependencies())
         .prompt()
         .unwrap();
     if dependencies.is_empty() {
-        return remove_dependencies();
+        return remove_dependencies(info);
     }
     for d in &dependencies {
         assert!(Command::new("cargo")
@@ -964,13 +3432,92 @@ fn remove_dependencies() -> bool {
     true
 }
 
-fn test_application() -> bool {
+fn test_application() -> bool {
+    Command::new("cargo")
+        .arg("test")
+        .arg("-j")
+        .arg("4")
+        .arg("--")
+        .arg("--show-output")
+        .current_dir(".")
+        .spawn()
+        .expect("cargo")
+        .wait()
+        .unwrap()
+        .success()
+}
+
+fn registry(package: &Package) -> Result<Option<String>, String> {
+    let configured = setting("publish", "registry");
+    match &package.publish {
+        None => Ok(configured),
+        Some(registries) if registries.is_empty() => {
+            Err(format!("{} has `publish = false`", package.name))
+        }
+        Some(registries) => {
+            if let Some(r) = configured.filter(|r| registries.contains(r)) {
+                return Ok(Some(r));
+            }
+            if registries.len().eq(&1) {
+                return Ok(registries.first().cloned());
+            }
+            Ok(Some(
+                Select::new(
+                    format!("Select the registry for {} : ", package.name).as_str(),
+                    registries.clone(),
+                )
+                .prompt()
+                .unwrap(),
+            ))
+        }
+    }
+}
+
+fn registry_allowed(package: &Package) -> bool {
+    package.publish.as_ref().is_none_or(|r| !r.is_empty())
+}
+
+fn publish(info: &ProjectInfo) -> bool {
+    let mut candidates: Vec<String> = info
+        .publish_order()
+        .into_iter()
+        .filter(|n| info.member(n).is_some_and(registry_allowed))
+        .collect();
+    if !info.is_workspace() {
+        candidates.retain(|n| n.eq(info.name()));
+    } else if !candidates.is_empty() {
+        let all: Vec<usize> = (0..candidates.len()).collect();
+        candidates = MultiSelect::new("Select the crates to publish : ", candidates)
+            .with_default(&all)
+            .prompt()
+            .unwrap();
+    }
+    if candidates.is_empty() {
+        println!("Nothing to publish: every crate has `publish = false`");
+        return true;
+    }
+    for name in &candidates {
+        let Some(package) = info.member(name) else {
+            continue;
+        };
+        match registry(package) {
+            Ok(r) => {
+                if !publish_package(name, r.as_deref()) {
+                    return false;
+                }
+            }
+            Err(e) => println!("Skipping {name}: {e}"),
+        }
+    }
+    true
+}
+
+fn publish_package(name: &str, registry: Option<&str>) -> bool {
     Command::new("cargo")
-        .arg("test")
-        .arg("-j")
-        .arg("4")
-        .arg("--")
-        .arg("--show-output")
+        .arg("publish")
+        .arg("-p")
+        .arg(name)
+        .args(registry.map(|r| format!("--registry={r}")))
         .current_dir(".")
         .spawn()
         .expect("cargo")
@@ -979,15 +3526,101 @@ fn test_application() -> bool {
         .success()
 }
 
-fn publish() -> bool {
-    Command::new("cargo")
-        .arg("publish")
+fn yank(info: &ProjectInfo) -> bool {
+    let released: Vec<String> = local_tags()
+        .into_iter()
+        .filter(|t| {
+            released_crate(info, t)
+                .and_then(|(name, _)| info.member(name.as_str()))
+                .is_some_and(registry_allowed)
+        })
+        .collect();
+    if released.is_empty() {
+        println!("There is no release tag to yank");
+        return true;
+    }
+    let tag = Select::new("Select the release : ", released)
+        .prompt()
+        .unwrap();
+    let (name, v) = released_crate(info, tag.as_str()).expect("msg");
+    let undo = Select::new("Action : ", vec!["yank", "undo the yank"])
+        .prompt()
+        .unwrap()
+        .eq("undo the yank");
+    let registry = match info.member(name.as_str()).map(registry) {
+        Some(Ok(r)) => r,
+        Some(Err(e)) => {
+            println!("{name} {v} cannot be yanked: {e}");
+            return true;
+        }
+        None => {
+            println!("{name} is not a member of the workspace");
+            return true;
+        }
+    };
+    if !Command::new("cargo")
+        .arg("yank")
+        .arg("--version")
+        .arg(v.to_string().as_str())
+        .args(undo.then_some("--undo"))
+        .args(registry.map(|r| format!("--registry={r}")))
+        .arg(name.as_str())
         .current_dir(".")
         .spawn()
         .expect("cargo")
         .wait()
         .unwrap()
         .success()
+    {
+        println!("cargo yank failed, the release notes of {name} {v} are unchanged");
+        return true;
+    }
+    let notice = if undo {
+        format!("> **Un-yanked** on {}\n\n", Utc::now().date_naive())
+    } else {
+        format!(
+            "> **Yanked** on {} : {}\n\n",
+            Utc::now().date_naive(),
+            ask("Enter the reason of the yank : ")
+        )
+    };
+    let notes = changelog_path(name.as_str(), v.to_string().as_str());
+    let content = read_to_string(notes.as_str()).unwrap_or_default();
+    let content = match content.split_once("\n\n") {
+        Some((title, rest)) if title.starts_with('#') => format!("{title}\n\n{notice}{rest}"),
+        _ => format!("{notice}{content}"),
+    };
+    if Path::new("./logs").is_dir().eq(&false) {
+        fs::create_dir_all("./logs").expect("msg");
+    }
+    fs::write(notes.as_str(), content).expect("failed to write the release notes");
+    let verb = if undo { "Un-yank" } else { "Yank" };
+    let committed = Command::new("git")
+        .arg("add")
+        .arg(notes.as_str())
+        .current_dir(".")
+        .spawn()
+        .expect("git")
+        .wait()
+        .unwrap()
+        .success()
+        && Command::new("git")
+            .arg("commit")
+            .args(sign("commits"))
+            .arg("-m")
+            .arg(format!("Launch({tag}): {verb} {name} version {v}").as_str())
+            .arg("--")
+            .arg(notes.as_str())
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success();
+    if !committed {
+        println!("Cannot commit the notice, {notes} is left modified");
+    }
+    true
 }
 
 fn send() -> bool {
@@ -1024,6 +3657,7 @@ fn status() -> bool {
 }
 
 fn flow(zuu: bool) {
+    let mut info = ProjectInfo::load(None);
     loop {
         clear();
         if zuu {
@@ -1033,8 +3667,15 @@ fn flow(zuu: bool) {
                     "Init a repository",
                     "Start a new feature",
                     "Finish a feature",
+                    "Start a release",
+                    "Finish a release",
+                    "Start a hotfix",
+                    "Finish a hotfix",
                     "Commit",
                     "Generate change log",
+                    "Bump version",
+                    "Release",
+                    "Select workspace member",
                     "Send modifications",
                     "Show status",
                     "Show branches",
@@ -1044,10 +3685,13 @@ fn flow(zuu: bool) {
                     "Run program",
                     "Remove dependencies",
                     "Publish",
+                    "Yank a release",
                     "Install",
                     "Update dependencies",
                     "Stash all modifications",
+                    "Manage stashes",
                     "Delete a branch",
+                    "Clean branches",
                     "Delete a tag",
                     "Create a new branch with no staged modifications",
                     "Show tags",
@@ -1060,16 +3704,39 @@ fn flow(zuu: bool) {
             match o {
                 "Init a repository" => assert!(init()),
 
-                "Start a new feature" => assert!(feature(
-                    ask("Enter the feature name").as_str(),
-                    &Verb::Start
-                )),
-                "Finish a feature" => assert!(feature(
-                    ask("Enter the feature name").as_str(),
-                    &Verb::Finish
-                )),
-                "Commit" => assert!(prepare_commit()),
-                "Generate change log" => assert!(create_changelog()),
+                "Start a new feature" => {
+                    assert!(feature(feature_name().as_str(), &Verb::Start));
+                }
+                "Finish a feature" => {
+                    if let Some((branch, strategy)) = paused_finish() {
+                        resume_finish(branch.as_str(), strategy);
+                    } else if let Some(name) = choose_feature() {
+                        feature(name.as_str(), &Verb::Finish);
+                    }
+                }
+                "Start a release" => {
+                    start_flow(RELEASE_BRANCH_PREFIX, info.as_mut());
+                }
+                "Finish a release" => {
+                    finish_flow(RELEASE_BRANCH_PREFIX, info.as_ref());
+                }
+                "Start a hotfix" => {
+                    start_flow(HOTFIX_BRANCH_PREFIX, info.as_mut());
+                }
+                "Finish a hotfix" => {
+                    finish_flow(HOTFIX_BRANCH_PREFIX, info.as_ref());
+                }
+                "Commit" => assert!(prepare_commit(info.as_mut())),
+                "Generate change log" => assert!(create_changelog(cargo(&mut info))),
+                "Bump version" => assert!(bump(cargo(&mut info))),
+                "Release" => {
+                    release(
+                        cargo(&mut info),
+                        confirm("Dry run only ?", true),
+                        confirm("Publish the crate ?", true),
+                    );
+                }
+                "Select workspace member" => assert!(cargo(&mut info).select()),
                 "Send modifications" => assert!(send()),
                 "Show status" => assert!(status()),
                 "Show branches" => assert!(display_branches()),
@@ -1077,14 +3744,17 @@ fn flow(zuu: bool) {
                 "Show logs" => assert!(logs()),
                 "Run tests" => assert!(test_application()),
                 "Run program" => assert!(run_program()),
-                "Remove dependencies" => assert!(remove_dependencies()),
-                "Publish" => assert!(publish()),
+                "Remove dependencies" => assert!(remove_dependencies(cargo(&mut info))),
+                "Publish" => assert!(publish(cargo(&mut info))),
+                "Yank a release" => assert!(yank(cargo(&mut info))),
                 "Install" => assert!(install_program()),
                 "Stash all modifications" => assert!(stash()),
+                "Manage stashes" => assert!(stashes()),
                 "Delete a branch" => assert!(remove_branch(
                     ask("Enter the name of the branch to remove : ").as_str()
                 )),
-                "Delete a tag" => assert!(delete_tag()),
+                "Clean branches" => assert!(cleanup()),
+                "Delete a tag" => assert!(delete_tag(info.as_ref())),
                 "Update dependencies" => assert!(update()),
                 "Create a new branch with no staged modifications" => assert!(stash_branch()),
                 "Show tags" => assert!(tags()),
@@ -1100,12 +3770,22 @@ fn flow(zuu: bool) {
 }
 
 fn stash_branch() -> bool {
+    let before = git_lines(&["stash", "list"]).len();
     assert!(stash());
-    let feat = ask("Enter the feature name");
+    if git_lines(&["stash", "list"]).len().eq(&before) {
+        println!("There is no local modification to move to a feature branch");
+        return true;
+    }
+    stash_to_feature("stash@{0}")
+}
+
+fn stash_to_feature(entry: &str) -> bool {
+    let name = feature_name();
     Command::new("git")
         .arg("stash")
         .arg("branch")
-        .arg(feat.as_str())
+        .arg(format!("{FEATURE_BRANCH_PREFIX}/{name}").as_str())
+        .arg(entry)
         .current_dir(".")
         .spawn()
         .expect("git")
@@ -1114,12 +3794,54 @@ fn stash_branch() -> bool {
         .success()
 }
 
-fn tags() -> bool {
+fn stashes() -> bool {
+    let mut entries: Vec<String> = git_lines(&["stash", "list", "--format=%gd | %cr | %gs"]);
+    entries.insert(0, String::from("Create a stash"));
+    let choice = Select::new("Select a stash : ", entries).prompt().unwrap();
+    if choice.eq("Create a stash") {
+        return stash();
+    }
+    let entry = choice.split(" | ").next().unwrap_or_default();
+    match Select::new(
+        format!("What you want do with {entry} : ").as_str(),
+        vec![
+            "Show diff",
+            "Apply",
+            "Pop",
+            "Drop",
+            "Create a feature branch",
+            "Back",
+        ],
+    )
+    .prompt()
+    .unwrap()
+    {
+        "Show diff" => Command::new("git")
+            .arg("stash")
+            .arg("show")
+            .arg("-p")
+            .arg("--include-untracked")
+            .arg(entry)
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success(),
+        "Apply" => git_stash("apply", entry),
+        "Pop" => git_stash("pop", entry),
+        "Drop" => !confirm(format!("Drop {entry} ?").as_str(), false) || git_stash("drop", entry),
+        "Create a feature branch" => stash_to_feature(entry),
+        _ => true,
+    }
+}
+
+fn git_stash(action: &str, entry: &str) -> bool {
     Command::new("git")
-        .arg("tag")
-        .arg("--list")
-        .arg("--sort=-taggerdate")
-        .arg("--format=%(refname:short) | %(objectname) | %(taggerdate:short) | %(subject)")
+        .arg("stash")
+        .arg(action)
+        .arg(entry)
+        .current_dir(".")
         .spawn()
         .expect("git")
         .wait()
@@ -1127,27 +3849,415 @@ fn tags() -> bool {
         .success()
 }
 
-fn delete_tag() -> bool {
-    let tag = ask("Enter the name of the tag to delete : ");
+fn verify_tag(name: &str) -> bool {
     Command::new("git")
-        .arg("tag")
-        .arg("-d")
-        .arg(tag.as_str())
-        .spawn()
+        .arg("verify-tag")
+        .arg(name)
+        .current_dir(".")
+        .output()
         .expect("git")
-        .wait()
-        .unwrap()
+        .status
         .success()
 }
 
+fn local_tags() -> Vec<String> {
+    String::from_utf8(
+        Command::new("git")
+            .arg("tag")
+            .arg("--list")
+            .current_dir(".")
+            .output()
+            .expect("git")
+            .stdout,
+    )
+    .expect("msg")
+    .lines()
+    .map(ToString::to_string)
+    .collect()
+}
+
+fn remote_tags() -> Option<Vec<String>> {
+    let output = Command::new("git")
+        .arg("ls-remote")
+        .arg("--tags")
+        .arg("--refs")
+        .arg("origin")
+        .current_dir(".")
+        .output()
+        .expect("git");
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8(output.stdout)
+            .expect("msg")
+            .lines()
+            .filter_map(|l| l.split_once("refs/tags/"))
+            .map(|(_, t)| t.to_string())
+            .collect(),
+    )
+}
+
+fn tags() -> bool {
+    let remote = remote_tags();
+    if remote.is_none() {
+        println!("origin is unreachable, the remote state of the tags is unknown");
+    }
+    let output = Command::new("git")
+        .arg("tag")
+        .arg("--list")
+        .arg("--sort=-taggerdate")
+        .arg("--format=%(refname:short) | %(objectname:short) | %(taggerdate:short) | %(subject)\x1f%(if)%(contents:signature)%(then)signed%(end)")
+        .current_dir(".")
+        .output()
+        .expect("git");
+    for line in String::from_utf8(output.stdout).expect("msg").lines() {
+        let (tag, signed) = line.split_once('\x1f').unwrap_or((line, ""));
+        let name = tag.split(" | ").next().unwrap_or_default();
+        let status = if signed.is_empty() {
+            "unsigned"
+        } else if verify_tag(name) {
+            "verified"
+        } else {
+            "bad or unknown signature"
+        };
+        match &remote {
+            Some(r) if !r.iter().any(|t| t.eq(name)) => {
+                println!("{tag} | {status} | not pushed");
+            }
+            Some(_) => println!("{tag} | {status} | pushed"),
+            None => println!("{tag} | {status}"),
+        }
+    }
+    let local = local_tags();
+    for orphan in remote
+        .unwrap_or_default()
+        .iter()
+        .filter(|t| !local.contains(t))
+    {
+        println!("{orphan} | only on origin");
+    }
+    output.status.success()
+}
+
+fn released_crate(info: &ProjectInfo, tag: &str) -> Option<(String, Version)> {
+    if info.is_workspace() {
+        return info.members().into_iter().find_map(|m| {
+            let v = Version::parse(tag.strip_prefix(format!("{m}-v").as_str())?).ok()?;
+            Some((m, v))
+        });
+    }
+    Some((
+        info.name().to_string(),
+        Version::parse(tag.trim_start_matches('v')).ok()?,
+    ))
+}
+
+fn published(package: &Package, v: &Version) -> Option<bool> {
+    let registries = package.publish.clone().unwrap_or_else(|| {
+        vec![setting("publish", "registry").unwrap_or_else(|| String::from("crates-io"))]
+    });
+    if registries.is_empty() {
+        return Some(false);
+    }
+    if registries.iter().any(|r| r.ne("crates-io")) {
+        return None;
+    }
+    let name = package.name.as_str();
+    let code = String::from_utf8(
+        Command::new("curl")
+            .arg("-s")
+            .arg("-o")
+            .arg("/dev/null")
+            .arg("-w")
+            .arg("%{http_code}")
+            .arg("-A")
+            .arg("commiter (https://github.com/otechdo/commiter)")
+            .arg(format!("https://crates.io/api/v1/crates/{name}/{v}").as_str())
+            .current_dir(".")
+            .output()
+            .ok()?
+            .stdout,
+    )
+    .ok()?;
+    match code.as_str() {
+        "200" => Some(true),
+        "404" => Some(false),
+        _ => None,
+    }
+}
+
+fn delete_tag(info: Option<&ProjectInfo>) -> bool {
+    let local = local_tags();
+    let remote = remote_tags().unwrap_or_default();
+    let mut all: Vec<String> = local.clone();
+    all.extend(remote.iter().filter(|t| !local.contains(t)).cloned());
+    if all.is_empty() {
+        println!("There is no tag to delete");
+        return true;
+    }
+    let tag = Select::new("Select the tag to delete : ", all)
+        .prompt()
+        .unwrap();
+    if let Some((package, v)) = info.and_then(|i| {
+        let (name, v) = released_crate(i, tag.as_str())?;
+        Some((i.member(name.as_str())?, v))
+    }) {
+        let name = package.name.as_str();
+        let forced = match published(package, &v) {
+            Some(false) => true,
+            Some(true) => {
+                println!("{name} {v} is published on crates.io, deleting {tag} breaks the link with the published sources");
+                confirm("Force the deletion ?", false)
+            }
+            None => {
+                println!("Cannot check whether {name} {v} is published on its registry");
+                confirm("Force the deletion ?", false)
+            }
+        };
+        if !forced {
+            return true;
+        }
+    }
+    if local.contains(&tag)
+        && !Command::new("git")
+            .arg("tag")
+            .arg("-d")
+            .arg(tag.as_str())
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success()
+    {
+        return false;
+    }
+    if remote.contains(&tag) && confirm(format!("Delete {tag} on origin ?").as_str(), false) {
+        return Command::new("git")
+            .arg("push")
+            .arg("--delete")
+            .arg("origin")
+            .arg(format!("refs/tags/{tag}").as_str())
+            .current_dir(".")
+            .spawn()
+            .expect("git")
+            .wait()
+            .unwrap()
+            .success();
+    }
+    true
+}
+
+fn table(rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            let w = cell.chars().count();
+            match widths.get_mut(i) {
+                Some(x) => *x = (*x).max(w),
+                None => widths.push(w),
+            }
+        }
+    }
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, w)| format!("{cell:<w$}"))
+            .collect();
+        println!("{}", cells.join(" | ").trim_end());
+    }
+}
+
 fn display_branches() -> bool {
-    Command::new("git")
-        .arg("show-branch")
-        .spawn()
-        .expect("git")
-        .wait()
+    let merged = if has_branch(DEV_BRANCH) {
+        git_lines(&[
+            "branch",
+            "--merged",
+            DEV_BRANCH,
+            "--format=%(refname:short)",
+        ])
+    } else {
+        Vec::new()
+    };
+    let mut rows: Vec<Vec<String>> = vec![[
+        "", "Branch", "Date", "Author", "Type", "Summary", "Upstream", "Ahead", "Behind", "Merged",
+    ]
+    .iter()
+    .map(ToString::to_string)
+    .collect()];
+    for line in git_lines(&[
+        "for-each-ref",
+        "--sort=-committerdate",
+        "--format=%(HEAD)%1f%(refname:short)%1f%(committerdate:short)%1f%(authorname)%1f%(upstream:short)%1f%(objectname)%1f%(subject)",
+        "refs/heads/",
+    ]) {
+        let fields: Vec<&str> = line.splitn(7, '\x1f').collect();
+        let [head, branch, date, author, upstream, hash, subject] = fields[..] else {
+            continue;
+        };
+        let tip = Commit::parse(format!("{hash}\x1f{subject}\x1f").as_str()).expect("msg");
+        let (ahead, behind) = if upstream.is_empty() {
+            (String::from("-"), String::from("-"))
+        } else {
+            let (a, b) = ahead_behind(branch, upstream);
+            (a.to_string(), b.to_string())
+        };
+        let state = if branch.eq(DEV_BRANCH) || !has_branch(DEV_BRANCH) {
+            "-"
+        } else if merged.iter().any(|m| m.eq(branch)) {
+            "merged"
+        } else {
+            "unmerged"
+        };
+        rows.push(vec![
+            head.to_string(),
+            branch.to_string(),
+            date.to_string(),
+            author.to_string(),
+            if tip.kind.is_empty() {
+                String::from("-")
+            } else {
+                tip.kind.clone()
+            },
+            tip.summary.clone(),
+            if upstream.is_empty() {
+                String::from("-")
+            } else {
+                upstream.to_string()
+            },
+            ahead,
+            behind,
+            state.to_string(),
+        ]);
+    }
+    table(&rows);
+    true
+}
+
+fn merged_into(targets: &[String], remote: bool) -> HashSet<String> {
+    let mut merged: HashSet<String> = HashSet::new();
+    for target in targets {
+        merged.extend(git_lines(&[
+            "branch",
+            if remote { "--remotes" } else { "--list" },
+            "--merged",
+            target.as_str(),
+            "--format=%(refname:short)",
+        ]));
+    }
+    merged
+}
+
+fn cleanup() -> bool {
+    let Some(main) = main_branch() else {
+        println!("Cannot find the main branch, set [flow] main in {CONFIG} before cleaning up");
+        return true;
+    };
+    let days: i64 = Text::new("Remove branches inactive for how many days ? ")
+        .with_default(number("cleanup", "days").unwrap_or(90).to_string().as_str())
+        .prompt()
         .unwrap()
-        .success()
+        .parse()
+        .unwrap_or(90);
+    if !git_ok(&["fetch", "--prune", "origin"]) {
+        println!("Cannot fetch origin, the remote branches may be outdated");
+    }
+    let current = git_lines(&["branch", "--show-current"]);
+    let protected: Vec<String> = vec![main.clone(), DEV_BRANCH.to_string()]
+        .into_iter()
+        .chain(current)
+        .collect();
+    let targets: Vec<String> = protected[..2]
+        .iter()
+        .filter(|b| has_branch(b))
+        .cloned()
+        .collect();
+    let now = Utc::now().timestamp();
+    let mut candidates: Vec<String> = Vec::new();
+    for (refs, remote) in [("refs/heads/", false), ("refs/remotes/origin/", true)] {
+        let merged = merged_into(&targets, remote);
+        for line in git_lines(&[
+            "for-each-ref",
+            "--format=%(refname:short)%1f%(committerdate:unix)",
+            refs,
+        ]) {
+            let Some((branch, date)) = line.split_once('\x1f') else {
+                continue;
+            };
+            let name = if remote {
+                branch.strip_prefix("origin/").unwrap_or(branch)
+            } else {
+                branch
+            };
+            if name.eq("HEAD") || name.eq("origin") || protected.iter().any(|p| p.eq(name)) {
+                continue;
+            }
+            let age = (now - date.parse::<i64>().unwrap_or(now)) / 86400;
+            let reason = if merged.contains(branch) {
+                String::from("merged")
+            } else if age.gt(&days) {
+                format!("inactive for {age} days")
+            } else {
+                continue;
+            };
+            candidates.push(format!(
+                "{} | {name} | {reason}",
+                if remote { "origin" } else { "local" }
+            ));
+        }
+    }
+    if candidates.is_empty() {
+        println!("There is no merged or stale branch to remove");
+        return true;
+    }
+    let chosen = MultiSelect::new("Select the branches to remove : ", candidates)
+        .prompt()
+        .unwrap();
+    for choice in &chosen {
+        let fields: Vec<&str> = choice.split(" | ").collect();
+        let [place, name, reason] = fields[..] else {
+            continue;
+        };
+        let merged = reason.eq("merged");
+        if !merged
+            && !confirm(
+                format!("{name} is not merged, remove it anyway ?").as_str(),
+                false,
+            )
+        {
+            continue;
+        }
+        let removed = if place.eq("origin") {
+            Command::new("git")
+                .arg("push")
+                .arg("origin")
+                .arg("--delete")
+                .arg(name)
+                .current_dir(".")
+                .spawn()
+                .expect("git")
+                .wait()
+                .unwrap()
+                .success()
+        } else {
+            Command::new("git")
+                .arg("branch")
+                .arg("-D")
+                .arg(name)
+                .current_dir(".")
+                .spawn()
+                .expect("git")
+                .wait()
+                .unwrap()
+                .success()
+        };
+        if !removed {
+            println!("Cannot remove {name} on {place}");
+        }
+    }
+    true
 }
 
 fn logs() -> bool {
@@ -1162,5 +4272,318 @@ fn logs() -> bool {
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|a| a.eq("release")) {
+        let mut info = ProjectInfo::load(None);
+        if !release(
+            cargo(&mut info),
+            args.iter().any(|a| a.eq("--dry-run")),
+            !args.iter().any(|a| a.eq("--no-publish")),
+        ) {
+            std::process::exit(1);
+        }
+        return;
+    }
     flow(zuu());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_parse_reads_a_conventional_subject() {
+        let c = Commit::parse("0123456789\x1fStar(ui)!: new layout\x1f").unwrap();
+        assert_eq!(c.short_hash(), "0123456");
+        assert_eq!(c.kind, "Star");
+        assert_eq!(c.scope, "ui");
+        assert_eq!(c.summary, "new layout");
+        assert_eq!(c.breaking(), Some(String::from("new layout")));
+    }
+
+    #[test]
+    fn commit_parse_reads_the_footers_of_a_plain_subject() {
+        let c =
+            Commit::parse("abc\x1fUpdate readme\x1fBREAKING CHANGE: drop v1\nMIGRATION: use v2\n")
+                .unwrap();
+        assert_eq!(c.short_hash(), "abc");
+        assert_eq!(c.kind, "");
+        assert_eq!(c.summary, "Update readme");
+        assert_eq!(c.breaking(), Some(String::from("drop v1")));
+        assert_eq!(c.migration(), Some(String::from("use v2")));
+        assert!(Commit::parse("abc\x1fno body").is_none());
+    }
+
+    fn hosted(kind: ForgeKind, url: &str) -> Forge {
+        Forge {
+            kind,
+            url: String::from(url),
+        }
+    }
+
+    #[test]
+    fn normalize_repository_returns_an_https_url() {
+        let url = "https://github.com/otechdo/commiter";
+        assert_eq!(
+            normalize_repository("git@github.com:otechdo/commiter.git"),
+            url
+        );
+        assert_eq!(
+            normalize_repository("ssh://git@github.com:22/otechdo/commiter"),
+            url
+        );
+        assert_eq!(
+            normalize_repository("https://github.com/otechdo/commiter.git/"),
+            url
+        );
+        assert_eq!(
+            normalize_repository("https://user@github.com/otechdo/commiter"),
+            url
+        );
+    }
+
+    #[test]
+    fn forge_kind_is_detected_from_the_host() {
+        assert!(ForgeKind::from_host("github.com") == ForgeKind::GitHub);
+        assert!(ForgeKind::from_host("gitlab.com") == ForgeKind::GitLab);
+        assert!(ForgeKind::from_host("gitlab.example.org") == ForgeKind::GitLab);
+        assert!(ForgeKind::from_host("gitea.example.org") == ForgeKind::Gitea);
+        assert!(ForgeKind::from_host("codeberg.org") == ForgeKind::Forgejo);
+        assert!(ForgeKind::from_host("forgejo.example.org") == ForgeKind::Forgejo);
+        assert!(ForgeKind::from_host("bitbucket.org") == ForgeKind::Bitbucket);
+        assert!(ForgeKind::from_host("git.sr.ht") == ForgeKind::SourceHut);
+        assert!(ForgeKind::from_host("git.example.org") == ForgeKind::Unknown);
+    }
+
+    #[test]
+    fn github_links() {
+        let f = hosted(ForgeKind::GitHub, "https://github.com/o/r");
+        assert_eq!(f.issues(), "https://github.com/o/r/issues");
+        assert_eq!(f.pulls().unwrap(), "https://github.com/o/r/pulls");
+        assert_eq!(
+            f.tag("v1.0.0"),
+            "https://github.com/o/r/releases/tag/v1.0.0"
+        );
+        assert_eq!(
+            f.file("./README.md"),
+            "https://github.com/o/r/blob/HEAD/README.md"
+        );
+    }
+
+    #[test]
+    fn gitlab_links() {
+        let f = hosted(ForgeKind::GitLab, "https://gitlab.com/o/r");
+        assert_eq!(f.issues(), "https://gitlab.com/o/r/-/issues");
+        assert_eq!(
+            f.pulls().unwrap(),
+            "https://gitlab.com/o/r/-/merge_requests"
+        );
+        assert_eq!(f.tag("v1.0.0"), "https://gitlab.com/o/r/-/tags/v1.0.0");
+        assert_eq!(
+            f.file("README.md"),
+            "https://gitlab.com/o/r/-/blob/HEAD/README.md"
+        );
+    }
+
+    #[test]
+    fn gitea_and_forgejo_links() {
+        for kind in [ForgeKind::Gitea, ForgeKind::Forgejo] {
+            let f = hosted(kind, "https://codeberg.org/o/r");
+            assert_eq!(f.issues(), "https://codeberg.org/o/r/issues");
+            assert_eq!(f.pulls().unwrap(), "https://codeberg.org/o/r/pulls");
+            assert_eq!(
+                f.tag("v1.0.0"),
+                "https://codeberg.org/o/r/releases/tag/v1.0.0"
+            );
+            let file = f.file("./LICENSE");
+            assert!(file.starts_with("https://codeberg.org/o/r/src/branch/"));
+            assert!(file.ends_with("/LICENSE"));
+        }
+    }
+
+    #[test]
+    fn bitbucket_links() {
+        let f = hosted(ForgeKind::Bitbucket, "https://bitbucket.org/o/r");
+        assert_eq!(f.issues(), "https://bitbucket.org/o/r/issues");
+        assert_eq!(
+            f.pulls().unwrap(),
+            "https://bitbucket.org/o/r/pull-requests"
+        );
+        assert_eq!(f.tag("v1.0.0"), "https://bitbucket.org/o/r/src/v1.0.0");
+        assert_eq!(
+            f.file("README.md"),
+            "https://bitbucket.org/o/r/src/HEAD/README.md"
+        );
+    }
+
+    #[test]
+    fn sourcehut_links() {
+        let f = hosted(ForgeKind::SourceHut, "https://git.sr.ht/~o/r");
+        assert_eq!(f.issues(), "https://todo.sr.ht/~o/r");
+        assert!(f.pulls().is_none());
+        assert_eq!(f.tag("v1.0.0"), "https://git.sr.ht/~o/r/refs/v1.0.0");
+        assert_eq!(
+            f.file("README.md"),
+            "https://git.sr.ht/~o/r/tree/HEAD/item/README.md"
+        );
+    }
+
+    #[test]
+    fn forge_commit_and_compare_links() {
+        let github = hosted(ForgeKind::GitHub, "https://github.com/o/r");
+        assert_eq!(
+            github.commit("abc").unwrap(),
+            "https://github.com/o/r/commit/abc"
+        );
+        assert_eq!(
+            github.compare("v1", "v2").unwrap(),
+            "https://github.com/o/r/compare/v1...v2"
+        );
+        let gitlab = hosted(ForgeKind::GitLab, "https://gitlab.com/o/r");
+        assert_eq!(
+            gitlab.commit("abc").unwrap(),
+            "https://gitlab.com/o/r/-/commit/abc"
+        );
+        assert_eq!(
+            gitlab.compare("a", "b").unwrap(),
+            "https://gitlab.com/o/r/-/compare/a...b"
+        );
+        let gitea = hosted(ForgeKind::Gitea, "https://gitea.example.org/o/r");
+        assert_eq!(
+            gitea.commit("abc").unwrap(),
+            "https://gitea.example.org/o/r/commit/abc"
+        );
+        assert_eq!(
+            gitea.compare("a", "b").unwrap(),
+            "https://gitea.example.org/o/r/compare/a...b"
+        );
+        let bitbucket = hosted(ForgeKind::Bitbucket, "https://bitbucket.org/o/r");
+        assert_eq!(
+            bitbucket.commit("abc").unwrap(),
+            "https://bitbucket.org/o/r/commits/abc"
+        );
+        assert_eq!(
+            bitbucket.compare("a", "b").unwrap(),
+            "https://bitbucket.org/o/r/branches/compare/b%0Da"
+        );
+        let sourcehut = hosted(ForgeKind::SourceHut, "https://git.sr.ht/~o/r");
+        assert_eq!(
+            sourcehut.commit("abc").unwrap(),
+            "https://git.sr.ht/~o/r/commit/abc"
+        );
+        assert!(sourcehut.compare("a", "b").is_none());
+    }
+
+    #[test]
+    fn forge_issue_links() {
+        let cases = [
+            (
+                ForgeKind::GitHub,
+                "https://github.com/o/r",
+                "https://github.com/o/r/issues/12",
+            ),
+            (
+                ForgeKind::GitLab,
+                "https://gitlab.com/o/r",
+                "https://gitlab.com/o/r/-/issues/12",
+            ),
+            (
+                ForgeKind::Forgejo,
+                "https://codeberg.org/o/r",
+                "https://codeberg.org/o/r/issues/12",
+            ),
+            (
+                ForgeKind::Bitbucket,
+                "https://bitbucket.org/o/r",
+                "https://bitbucket.org/o/r/issues/12",
+            ),
+            (
+                ForgeKind::SourceHut,
+                "https://git.sr.ht/~o/r",
+                "https://todo.sr.ht/~o/r/12",
+            ),
+        ];
+        for (kind, url, issue) in cases {
+            assert_eq!(hosted(kind, url).issue("12").unwrap(), issue);
+        }
+    }
+
+    #[test]
+    fn unknown_forges_leave_issues_and_commits_unlinked() {
+        let f = hosted(ForgeKind::Unknown, "https://git.example.org/o/r");
+        assert!(f.issue("12").is_none());
+        assert!(f.commit("abc").is_none());
+        assert_eq!(linkify("Fix the crash (#12)", &f), "Fix the crash (#12)");
+        let c = Commit::parse("0123456789\x1fStar: x (#12)\x1fFixes #12\n").unwrap();
+        assert_eq!(change(&c, &f), "- x (#12) (`0123456`), fixes #12\n");
+    }
+
+    #[test]
+    fn commit_references_read_the_closing_keywords() {
+        let c = Commit::parse("abc\x1fStar: x\x1fFixes #12, #13\nCloses #4\nSee #5\n").unwrap();
+        assert_eq!(
+            c.references(),
+            vec![
+                (String::from("fixes"), String::from("12")),
+                (String::from("fixes"), String::from("13")),
+                (String::from("closes"), String::from("4"))
+            ]
+        );
+    }
+
+    fn v(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    #[test]
+    fn bump_version_increments_the_requested_part() {
+        assert_eq!(bump_version(&v("1.2.3"), Bump::Major), v("2.0.0"));
+        assert_eq!(bump_version(&v("1.2.3"), Bump::Minor), v("1.3.0"));
+        assert_eq!(bump_version(&v("1.2.3"), Bump::Patch), v("1.2.4"));
+    }
+
+    #[test]
+    fn bump_version_turns_a_major_bump_into_a_minor_before_1_0() {
+        assert_eq!(bump_version(&v("0.4.2"), Bump::Major), v("0.5.0"));
+        assert_eq!(bump_version(&v("0.4.2"), Bump::Patch), v("0.4.3"));
+    }
+
+    #[test]
+    fn bump_version_promotes_a_pre_release() {
+        assert_eq!(bump_version(&v("1.2.0-rc.1"), Bump::Major), v("1.2.0"));
+    }
+
+    #[test]
+    fn pre_release_starts_and_increments_a_channel() {
+        assert_eq!(pre_release(&v("1.1.0"), &v("1.2.0"), "rc"), v("1.2.0-rc.1"));
+        assert_eq!(
+            pre_release(&v("1.2.0-rc.1"), &v("1.2.0"), "rc"),
+            v("1.2.0-rc.2")
+        );
+        assert_eq!(
+            pre_release(&v("1.2.0-beta.3"), &v("1.2.0"), "rc"),
+            v("1.2.0-rc.1")
+        );
+    }
+
+    #[test]
+    fn channels_never_go_back() {
+        assert_eq!(channels(&v("1.2.0")), vec!["alpha", "beta", "rc"]);
+        assert_eq!(channels(&v("1.2.0-beta.2")), vec!["beta", "rc"]);
+        assert_eq!(channels(&v("1.2.0-rc.1")), vec!["rc"]);
+    }
+
+    #[test]
+    fn supersedes_follows_the_semver_precedence() {
+        assert!(supersedes(&v("1.2.0-rc.2"), &v("1.2.0-rc.1")));
+        assert!(supersedes(&v("1.2.0"), &v("1.2.0-rc.2")));
+        assert!(!supersedes(&v("1.2.0-rc.1"), &v("1.2.0")));
+        assert!(!supersedes(&v("1.2.0+build.5"), &v("1.2.0")));
+    }
+
+    #[test]
+    fn slugify_keeps_lowercase_words() {
+        assert_eq!(slugify("Add the Login page!"), "add-the-login-page");
+        assert_eq!(slugify("  fix: crash #42 "), "fix-crash-42");
+    }
+}